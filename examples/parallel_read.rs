@@ -0,0 +1,62 @@
+//! Compare `read_from_file` with `read_from_file_parallel` on a large video.
+//!
+//! ```text
+//! cargo run --release --example parallel_read --features parallel [frames]
+//! ```
+
+use std::{env, fs, time::Instant};
+
+use ascii_assets::{AsciiSprite, AsciiVideo, Color, TerminalChar};
+
+const WIDTH: u16 = 200;
+const HEIGHT: u16 = 50;
+
+fn main() -> std::io::Result<()> {
+    let frame_count: u32 = env::args()
+        .nth(1)
+        .map(|n| n.parse().expect("frame count must be a number"))
+        .unwrap_or(3000);
+
+    let frames = (0..frame_count)
+        .map(|i| {
+            let pixels = (0..WIDTH as u32 * HEIGHT as u32)
+                .map(|j| {
+                    let chr = char::from_u32(0x21 + (i + j) % 90).unwrap();
+                    match (i + j) % 3 {
+                        0 => TerminalChar::from_char(chr),
+                        1 => TerminalChar::with_fg(chr, Color::rgb(i as u8, j as u8, 7)),
+                        _ => TerminalChar::with_colors(chr, Color::Red, Color::Navy),
+                    }
+                })
+                .collect();
+            AsciiSprite::new(WIDTH, HEIGHT, pixels).unwrap()
+        })
+        .collect();
+    let video = AsciiVideo::new(WIDTH, HEIGHT, frames)?;
+
+    let path = env::temp_dir().join("ascii_assets_parallel_read.ascv");
+    video.write_to_file(&path)?;
+    let size = fs::metadata(&path)?.len();
+
+    let start = Instant::now();
+    let serial = AsciiVideo::read_from_file(&path)?;
+    let serial_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel = AsciiVideo::read_from_file_parallel(&path)?;
+    let parallel_time = start.elapsed();
+    fs::remove_file(&path)?;
+
+    assert_eq!(serial, parallel);
+    println!(
+        "{} frames of {}x{} ({:.1} MB), {} threads",
+        frame_count,
+        WIDTH,
+        HEIGHT,
+        size as f64 / 1e6,
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    );
+    println!("serial:   {:.2?}", serial_time);
+    println!("parallel: {:.2?}", parallel_time);
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    fmt::{self, Write},
+    time::Duration,
+};
+
+use crate::{AsciiSprite, AsciiVideo, Color, TerminalChar};
+
+/// Which colours a terminal understands, and so which SGR sequences
+/// rendering emits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// 24-bit colours, `38;2;r;g;b`.
+    #[default]
+    TrueColor,
+    /// The xterm 256 colour palette, `38;5;n`.
+    Ansi256,
+    /// The 16 basic colours, `30`-`37` and `90`-`97`.
+    Ansi16,
+}
+
+/// A colour as it is sent to the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sgr {
+    Rgb(u8, u8, u8),
+    Indexed(u8),
+    Basic(u8),
+}
+
+impl ColorDepth {
+    /// Convert a colour to this depth, `None` for the terminal default.
+    fn sgr(self, col: Option<Color>) -> Option<Sgr> {
+        let col = col.filter(|col| !col.reset)?;
+        match self {
+            ColorDepth::TrueColor => {
+                let (r, g, b) = col.rgb;
+                Some(Sgr::Rgb(r, g, b))
+            }
+            ColorDepth::Ansi256 => col.as_ansi256().map(Sgr::Indexed),
+            ColorDepth::Ansi16 => col.as_ansi16().map(Sgr::Basic),
+        }
+    }
+}
+
+impl ColorDepth {
+    /// The lowest depth that shows `col` exactly, with a rank to compare
+    /// depths by.
+    fn required_for(col: Color) -> (u8, ColorDepth) {
+        let exact16 = col.as_ansi16().map(Color::from_ansi16) == Some(col);
+        let exact256 = col.as_ansi256().map(Color::ansi256_to_rgb) == Some(col.rgb);
+        match (exact16, exact256) {
+            (true, _) => (0, ColorDepth::Ansi16),
+            (_, true) => (1, ColorDepth::Ansi256),
+            _ => (2, ColorDepth::TrueColor),
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// The lowest colour depth that renders every colour of the sprite
+    /// exactly: [`ColorDepth::Ansi16`] if all are among the 16 named
+    /// colours, [`ColorDepth::Ansi256`] if [`Color::as_ansi256`] converts
+    /// all without loss, otherwise [`ColorDepth::TrueColor`]. Unset and reset colours
+    /// need no depth at all, so a sprite without colours gives `Ansi16`.
+    pub fn required_color_depth(&self) -> ColorDepth {
+        self.pixels
+            .iter()
+            .flat_map(|p| [p.fg_color, p.bg_color])
+            .flatten()
+            .filter(|col| !col.reset)
+            .map(ColorDepth::required_for)
+            .max_by_key(|(rank, _)| *rank)
+            .map_or(ColorDepth::Ansi16, |(_, depth)| depth)
+    }
+}
+
+/// Tracks the colours the terminal currently has set, so escape sequences
+/// are only emitted when a cell actually changes them.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SgrState {
+    depth: ColorDepth,
+    fg: Option<Sgr>,
+    bg: Option<Sgr>,
+}
+
+impl SgrState {
+    pub(crate) fn new(depth: ColorDepth) -> Self {
+        Self {
+            depth,
+            ..Self::default()
+        }
+    }
+
+    /// Write `c` preceded by whatever SGR sequence is needed to switch to its colours.
+    ///
+    /// Going back to a default colour resets all attributes with `0`,
+    /// followed by the colour that stays set, if any.
+    pub(crate) fn write_char<W: Write>(&mut self, out: &mut W, c: &TerminalChar) -> fmt::Result {
+        let fg = self.depth.sgr(c.fg_color);
+        let bg = self.depth.sgr(c.bg_color);
+
+        if fg != self.fg || bg != self.bg {
+            out.write_str("\x1b[")?;
+            let mut first = true;
+            if (self.fg.is_some() && fg.is_none()) || (self.bg.is_some() && bg.is_none()) {
+                out.write_char('0')?;
+                first = false;
+                self.fg = None;
+                self.bg = None;
+            }
+            if let Some(col) = fg.filter(|_| fg != self.fg) {
+                write_param(out, &mut first, col, 30)?;
+            }
+            if let Some(col) = bg.filter(|_| bg != self.bg) {
+                write_param(out, &mut first, col, 40)?;
+            }
+            out.write_char('m')?;
+            self.fg = fg;
+            self.bg = bg;
+        }
+        out.write_char(c.chr)
+    }
+
+    /// Restore the terminal defaults if any colour is still active.
+    pub(crate) fn finish<W: Write>(&mut self, out: &mut W) -> fmt::Result {
+        if self.fg.is_some() || self.bg.is_some() {
+            out.write_str("\x1b[0m")?;
+            self.fg = None;
+            self.bg = None;
+        }
+        Ok(())
+    }
+}
+
+/// Write a single SGR colour parameter, `base` being 30 for the foreground
+/// and 40 for the background.
+fn write_param<W: Write>(out: &mut W, first: &mut bool, col: Sgr, base: u8) -> fmt::Result {
+    if !*first {
+        out.write_char(';')?;
+    }
+    *first = false;
+    match col {
+        Sgr::Rgb(r, g, b) => write!(out, "{};2;{};{};{}", base + 8, r, g, b),
+        Sgr::Indexed(n) => write!(out, "{};5;{}", base + 8, n),
+        Sgr::Basic(n) if n < 8 => write!(out, "{}", base + n),
+        Sgr::Basic(n) => write!(out, "{}", base + 60 + (n - 8)),
+    }
+}
+
+/// Delay between frames of a video without timing in playback scripts.
+const UNTIMED_FRAME: Duration = Duration::from_millis(100);
+
+impl AsciiVideo {
+    /// Render every frame with [`crate::AsciiSprite::to_ansi`].
+    ///
+    /// With `cursor_home` every frame starts with `\x1b[H`, so printing the
+    /// frames one after another draws each over the last instead of below it.
+    pub fn to_ansi_frames(&self, depth: ColorDepth, cursor_home: bool) -> Vec<String> {
+        self.frames
+            .iter()
+            .map(|frame| {
+                let ansi = frame.to_ansi(depth);
+                if cursor_home {
+                    format!("\x1b[H{}", ansi)
+                } else {
+                    ansi
+                }
+            })
+            .collect()
+    }
+
+    /// A POSIX shell script that replays the video in a terminal: it clears
+    /// the screen, then draws every frame in place and sleeps for its
+    /// [`AsciiVideo::frame_duration`], 100ms if the video has no timing.
+    /// Looping videos repeat until interrupted.
+    pub fn to_ansi_playback_script(&self, depth: ColorDepth) -> String {
+        let mut script = String::from("#!/bin/sh\nprintf '\\033[2J'\n");
+        let indent = if self.looping {
+            script.push_str("while :; do\n");
+            "  "
+        } else {
+            ""
+        };
+        for (i, frame) in self.to_ansi_frames(depth, true).iter().enumerate() {
+            let delay = self.frame_duration(i).unwrap_or(UNTIMED_FRAME);
+            // single quotes can't be escaped inside single quotes, so close
+            // the string, add an escaped quote and reopen it
+            let quoted = frame.replace('\'', "'\\''");
+            let _ = writeln!(
+                script,
+                "{}printf '%s\\n' '{}'\n{}sleep {}.{:03}",
+                indent,
+                quoted,
+                indent,
+                delay.as_secs(),
+                delay.subsec_millis()
+            );
+        }
+        if self.looping {
+            script.push_str("done\n");
+        }
+        script
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiSprite;
+    use alloc::vec;
+
+    #[test]
+    fn test_required_color_depth() {
+        let mut sprite = AsciiSprite::from_rows(vec!["abc".into()]).unwrap();
+        assert_eq!(sprite.required_color_depth(), ColorDepth::Ansi16);
+        sprite.pixels[0] = TerminalChar::with_colors('a', Color::Teal, Color::Reset);
+        assert_eq!(sprite.required_color_depth(), ColorDepth::Ansi16);
+        sprite.pixels[1].bg_color = Some(Color::from_ansi256(208));
+        assert_eq!(sprite.required_color_depth(), ColorDepth::Ansi256);
+        sprite.pixels[2].fg_color = Some(Color::rgb(1, 2, 3));
+        assert_eq!(sprite.required_color_depth(), ColorDepth::TrueColor);
+        sprite.pixels[2].fg_color = None;
+        assert_eq!(sprite.required_color_depth(), ColorDepth::Ansi256);
+    }
+
+    #[test]
+    fn test_ansi_frames_and_script() {
+        let frames = ["it's", "done"]
+            .iter()
+            .map(|row| AsciiSprite::from_rows(vec![(*row).into()]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(4, 1, frames).unwrap();
+        video.frames[1].pixels[0].fg_color = Some(Color::Red);
+
+        let plain = video.to_ansi_frames(ColorDepth::TrueColor, false);
+        assert_eq!(plain, ["it's", "\x1b[38;2;255;0;0md\x1b[0mone"]);
+        let homed = video.to_ansi_frames(ColorDepth::Ansi16, true);
+        assert_eq!(homed[1], "\x1b[H\x1b[91md\x1b[0mone");
+
+        video.frame_durations = vec![Duration::from_millis(1500), Duration::from_millis(40)];
+        let script = video.to_ansi_playback_script(ColorDepth::TrueColor);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("printf '%s\\n' '\x1b[Hit'\\''s'\nsleep 1.500\n"));
+        assert!(script.ends_with("sleep 0.040\n"));
+
+        video.looping = true;
+        let script = video.to_ansi_playback_script(ColorDepth::TrueColor);
+        assert!(script.contains("while :; do\n  printf"));
+        assert!(script.ends_with("  sleep 0.040\ndone\n"));
+    }
+}
@@ -0,0 +1,276 @@
+//! Import of DOS-era ANSI art: CP437 text with ANSI.SYS escape sequences.
+
+use alloc::{format, vec, vec::Vec};
+
+use crate::{AsciiSprite, Color, TerminalChar, io};
+
+/// Unicode equivalents of the CP437 bytes `0x80..=0xFF`.
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Glyphs CP437 shows for the control bytes `0x00..0x20`.
+const CP437_LOW: [char; 32] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', //
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+];
+
+/// Decode a single CP437 byte.
+fn cp437(b: u8) -> char {
+    match b {
+        0x00..0x20 => CP437_LOW[b as usize],
+        0x7F => '⌂',
+        0x80.. => CP437_HIGH[(b - 0x80) as usize],
+        _ => b as char,
+    }
+}
+
+/// Cursor and colour state of the emulated ANSI.SYS terminal.
+struct Screen {
+    width: u16,
+    rows: Vec<Vec<TerminalChar>>,
+    x: u16,
+    y: u16,
+    saved: (u16, u16),
+    /// Set after writing the last column: the next character wraps first.
+    pending_wrap: bool,
+    fg: Option<u8>,
+    bg: Option<u8>,
+    bold: bool,
+    reverse: bool,
+}
+
+impl Screen {
+    fn put(&mut self, chr: char) -> io::Result<()> {
+        if self.pending_wrap {
+            self.newline();
+        }
+        if self.y == u16::MAX {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "ANSI art is taller than 65535 rows",
+            ));
+        }
+        let (mut fg, mut bg) = (self.fg, self.bg);
+        if self.reverse {
+            // reversing swaps the defaults too, grey on black
+            (fg, bg) = (Some(bg.unwrap_or(0)), Some(fg.unwrap_or(7)));
+        }
+        if self.bold {
+            fg = Some(fg.unwrap_or(7) | 8);
+        }
+        let cell = TerminalChar {
+            chr,
+            fg_color: fg.map(Color::from_ansi16),
+            bg_color: bg.map(Color::from_ansi16),
+        };
+
+        while self.rows.len() <= self.y as usize {
+            self.rows
+                .push(vec![TerminalChar::default(); self.width as usize]);
+        }
+        self.rows[self.y as usize][self.x as usize] = cell;
+        if self.x + 1 < self.width {
+            self.x += 1;
+        } else {
+            self.pending_wrap = true;
+        }
+        Ok(())
+    }
+
+    fn newline(&mut self) {
+        self.x = 0;
+        self.y = self.y.saturating_add(1);
+        self.pending_wrap = false;
+    }
+
+    fn move_to(&mut self, x: i32, y: i32) {
+        self.x = x.clamp(0, self.width as i32 - 1) as u16;
+        self.y = y.clamp(0, u16::MAX as i32) as u16;
+        self.pending_wrap = false;
+    }
+
+    /// Apply the control sequence `ESC [ params final`.
+    fn control(&mut self, params: &[u16], final_byte: u8) {
+        let n = params.first().copied().unwrap_or(0).max(1) as i32;
+        let (x, y) = (self.x as i32, self.y as i32);
+        match final_byte {
+            b'A' => self.move_to(x, y - n),
+            b'B' => self.move_to(x, y + n),
+            b'C' => self.move_to(x + n, y),
+            b'D' => self.move_to(x - n, y),
+            b'H' | b'f' => {
+                let row = params.first().copied().unwrap_or(1).max(1) as i32;
+                let col = params.get(1).copied().unwrap_or(1).max(1) as i32;
+                self.move_to(col - 1, row - 1);
+            }
+            b'J' if params.first() == Some(&2) => {
+                self.rows.clear();
+                self.move_to(0, 0);
+            }
+            b'K' => {
+                if let Some(row) = self.rows.get_mut(self.y as usize) {
+                    row[self.x as usize..].fill(TerminalChar::default());
+                }
+            }
+            b's' => self.saved = (self.x, self.y),
+            b'u' => self.move_to(self.saved.0 as i32, self.saved.1 as i32),
+            b'm' => self.sgr(params),
+            _ => {}
+        }
+    }
+
+    fn sgr(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            return self.sgr(&[0]);
+        }
+        for &p in params {
+            match p {
+                0 => {
+                    self.fg = None;
+                    self.bg = None;
+                    self.bold = false;
+                    self.reverse = false;
+                }
+                1 => self.bold = true,
+                22 => self.bold = false,
+                7 => self.reverse = true,
+                27 => self.reverse = false,
+                30..=37 => self.fg = Some((p - 30) as u8),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some((p - 40) as u8),
+                49 => self.bg = None,
+                90..=97 => self.fg = Some((p - 90) as u8 + 8),
+                100..=107 => self.bg = Some((p - 100) as u8 + 8),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// Import classic ANSI art, like `.ans` files, laid out `width` columns wide.
+    ///
+    /// Bytes are decoded as CP437, the DOS code page, and the ANSI.SYS
+    /// sequences for colours (SGR), cursor movement, saving and restoring
+    /// the cursor and clearing are applied; others are ignored. Colours map
+    /// to the 16 named ANSI colours, bold brightens the foreground, and text
+    /// without any colour set keeps the terminal defaults. Lines wrap after
+    /// `width` columns. The height is the number of rows that were written
+    /// to. Reading stops at the DOS end of file marker `0x1A`, which
+    /// precedes the SAUCE metadata record of many files.
+    ///
+    /// ## Error
+    /// if `width` is 0 or the art is taller than the largest sprite
+    pub fn from_ansi_art(bytes: &[u8], width: u16) -> io::Result<AsciiSprite> {
+        if width == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ANSI art width must be non-zero",
+            ));
+        }
+        let mut screen = Screen {
+            width,
+            rows: Vec::new(),
+            x: 0,
+            y: 0,
+            saved: (0, 0),
+            pending_wrap: false,
+            fg: None,
+            bg: None,
+            bold: false,
+            reverse: false,
+        };
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            i += 1;
+            match b {
+                0x1A => break,
+                b'\r' => screen.move_to(0, screen.y as i32),
+                b'\n' => screen.newline(),
+                b'\t' => {
+                    for _ in 0..8 - screen.x % 8 {
+                        screen.put(' ')?;
+                    }
+                }
+                0x1B if bytes.get(i) == Some(&b'[') => {
+                    i += 1;
+                    let mut params = Vec::new();
+                    let mut current: Option<u16> = None;
+                    while let Some(&c) = bytes.get(i) {
+                        i += 1;
+                        match c {
+                            b'0'..=b'9' => {
+                                let digit = (c - b'0') as u16;
+                                current = Some(current.unwrap_or(0).saturating_mul(10) + digit);
+                            }
+                            b';' => params.push(current.take().unwrap_or(0)),
+                            0x40..=0x7E => {
+                                params.extend(current);
+                                screen.control(&params, c);
+                                break;
+                            }
+                            // private markers like `?`, which don't change anything here
+                            _ => {}
+                        }
+                    }
+                }
+                0x1B => {}
+                _ => screen.put(cp437(b))?,
+            }
+        }
+
+        let height = screen.rows.len();
+        let pixels = screen.rows.into_iter().flatten().collect();
+        AsciiSprite::new(width, height as u16, pixels)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("ANSI art: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ansi_art() {
+        let art = b"\x1b[0;1;31mA\x1b[0;44m\xdb\xb0\x1b[m\r\n\x1b[2C\x01\x1b[s\x1b[1;1H!\x1b[u!\x1a\x1b[1mjunk";
+        let sprite = AsciiSprite::from_ansi_art(art, 4).unwrap();
+        assert_eq!((sprite.width, sprite.height), (4, 2));
+        assert_eq!(sprite.to_plain_string(), "!█░ \n  ☺!");
+        assert_eq!(sprite.pixels[0], TerminalChar::from('!'));
+        assert_eq!(
+            sprite.pixels[1],
+            TerminalChar::with_bg('█', Color::from_ansi16(4))
+        );
+        assert_eq!(sprite.pixels[6], TerminalChar::from('☺'));
+
+        let sprite = AsciiSprite::from_ansi_art(b"\x1b[1;31mR\x1b[7mV", 2).unwrap();
+        assert_eq!(
+            sprite.pixels[0],
+            TerminalChar::with_fg('R', Color::from_ansi16(9))
+        );
+        assert_eq!(
+            sprite.pixels[1],
+            // bold brightens the swapped in default black
+            TerminalChar::with_colors('V', Color::from_ansi16(8), Color::from_ansi16(1))
+        );
+
+        // exactly full lines followed by CRLF don't leave blank lines
+        let sprite = AsciiSprite::from_ansi_art(b"ab\r\ncd\r\nef", 2).unwrap();
+        assert_eq!(sprite.to_plain_string(), "ab\ncd\nef");
+        let sprite = AsciiSprite::from_ansi_art(b"abcde", 2).unwrap();
+        assert_eq!(sprite.to_plain_string(), "ab\ncd\ne ");
+
+        assert_eq!(AsciiSprite::from_ansi_art(b"", 3).unwrap().height, 0);
+        assert!(AsciiSprite::from_ansi_art(b"x", 0).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::{format, vec::Vec};
+
+use crate::{AsciiSprite, TerminalChar, cell_count, index, io};
+
+/// A read-only sprite decoding its cells straight from encoded bytes.
+///
+/// Cells are variable-size on disk (colours are optional), so the view keeps
+/// the offset of every cell, found and validated once in
+/// [`AsciiSprite::view_bytes`]. [`BorrowedSprite::get`] then decodes a single
+/// cell without touching the others.
+#[derive(Debug, Clone)]
+pub struct BorrowedSprite<'a> {
+    bytes: &'a [u8],
+    width: u16,
+    height: u16,
+    offsets: Vec<usize>,
+}
+
+impl<'a> BorrowedSprite<'a> {
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Decode the character at the given coordinates, or `None` if out of bounds.
+    pub fn get(&self, x: u16, y: u16) -> Option<TerminalChar> {
+        if y >= self.height {
+            return None;
+        }
+        let idx = index(x, y, self.width)?;
+        TerminalChar::read_from(&mut &self.bytes[self.offsets[idx]..]).ok()
+    }
+
+    /// Decode every cell into an owned sprite.
+    pub fn to_sprite(&self) -> AsciiSprite {
+        let mut r = self.bytes;
+        let pixels = (0..self.offsets.len())
+            .map(|_| TerminalChar::read_from(&mut r).expect("validated by view_bytes"))
+            .collect();
+        AsciiSprite {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// View a frame encoded by [`AsciiSprite::write_to`] without decoding it.
+    ///
+    /// ## Error
+    /// if `bytes` doesn't hold exactly `width * height` valid characters
+    pub fn view_bytes(bytes: &[u8], width: u16, height: u16) -> io::Result<BorrowedSprite<'_>> {
+        let cells = cell_count(width, height, io::ErrorKind::InvalidInput)?;
+        let mut offsets = Vec::with_capacity(cells);
+        let mut rest = bytes;
+        for _ in 0..cells {
+            offsets.push(bytes.len() - rest.len());
+            TerminalChar::read_from(&mut rest)?;
+        }
+        if !rest.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} trailing bytes after {}x{} sprite",
+                    rest.len(),
+                    width,
+                    height
+                ),
+            ));
+        }
+        Ok(BorrowedSprite {
+            bytes,
+            width,
+            height,
+            offsets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use alloc::vec;
+
+    #[test]
+    fn test_view_bytes() {
+        let pixels = vec![
+            TerminalChar::from('a'),
+            TerminalChar::with_fg('b', Color::Red),
+            TerminalChar::with_colors('c', Color::Lime, Color::Navy),
+            TerminalChar::with_bg('d', Color::Olive),
+        ];
+        let sprite = AsciiSprite::new(2, 2, pixels).unwrap();
+        let mut bytes = Vec::new();
+        sprite.write_to(&mut bytes).unwrap();
+
+        let view = AsciiSprite::view_bytes(&bytes, 2, 2).unwrap();
+        assert_eq!(view.get(0, 1), sprite.get_char(0, 1));
+        assert_eq!(view.get(1, 1), sprite.get_char(1, 1));
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.to_sprite(), sprite);
+
+        let short = AsciiSprite::view_bytes(&bytes[..bytes.len() - 1], 2, 2);
+        assert_eq!(short.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        bytes.push(0);
+        let long = AsciiSprite::view_bytes(&bytes, 2, 2);
+        assert_eq!(long.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::{format, vec::Vec};
+
+use crate::{AsciiSprite, AsciiVideo, frame_period, io};
+
+/// Assembles an [`AsciiVideo`] frame by frame.
+///
+/// Frames are checked as they are pushed, so a frame of the wrong size is
+/// reported where it is added instead of when the video is created.
+#[derive(Debug, Clone)]
+pub struct AsciiVideoBuilder {
+    width: u16,
+    height: u16,
+    frames: Vec<AsciiSprite>,
+    frame_rate: Option<f32>,
+}
+
+impl AsciiVideoBuilder {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            frames: Vec::new(),
+            frame_rate: None,
+        }
+    }
+
+    /// Set the frame rate of the video.
+    pub fn with_frame_rate(mut self, fps: f32) -> Self {
+        self.frame_rate = Some(fps);
+        self
+    }
+
+    /// Append a frame.
+    ///
+    /// ## Error
+    /// if the frame doesn't have the dimensions given to [`AsciiVideoBuilder::new`]
+    pub fn push_frame(&mut self, frame: AsciiSprite) -> io::Result<&mut Self> {
+        if frame.width != self.width || frame.height != self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Frame {} has size {}x{} but expected {}x{}",
+                    self.frames.len(),
+                    frame.width,
+                    frame.height,
+                    self.width,
+                    self.height
+                ),
+            ));
+        }
+        self.frames.push(frame);
+        Ok(self)
+    }
+
+    /// Create the video.
+    ///
+    /// ## Error
+    /// if the frame rate isn't a positive number, or the dimensions are
+    /// rejected by [`AsciiVideo::new`]
+    pub fn build(self) -> io::Result<AsciiVideo> {
+        if let Some(fps) = self.frame_rate.filter(|&fps| frame_period(fps).is_none()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid frame rate {}", fps),
+            ));
+        }
+        let mut video = AsciiVideo::new(self.width, self.height, self.frames)?;
+        video.frame_rate = self.frame_rate;
+        Ok(video)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerminalChar;
+    use alloc::{string::ToString, vec};
+
+    #[test]
+    fn test_builder_rejects_bad_frames() {
+        let frame = AsciiSprite::new(2, 2, vec![TerminalChar::from('o'); 4]).unwrap();
+        let wrong = AsciiSprite::new(2, 1, vec![TerminalChar::from('o'); 2]).unwrap();
+
+        let mut builder = AsciiVideoBuilder::new(2, 2);
+        builder.push_frame(frame.clone()).unwrap();
+        let err = builder.push_frame(wrong).unwrap_err();
+        assert!(err.to_string().starts_with("Frame 1 has size 2x1"));
+        builder.push_frame(frame).unwrap();
+        assert_eq!(builder.clone().build().unwrap().frames.len(), 2);
+        assert!(builder.with_frame_rate(-1.0).build().is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! The binary encoding of a single [`TerminalChar`], shared by every file
+//! format of the crate.
+//!
+//! A cell is its code point, stored as [`CharEncoding`] specifies, followed
+//! by the foreground and then the background colour. Each colour is a flag
+//! byte, `1` followed by 3 bytes of RGB if it is set, otherwise `0`.
+//! Reset colours are stored as not set. Inside videos whose header marks
+//! palette indices, a colour from [`Color::ansi256_exact`] is `2` followed
+//! by the index instead.
+
+use alloc::vec::Vec;
+
+use byteorder::LittleEndian;
+
+use crate::{
+    CharEncoding, Color, TerminalChar,
+    io::{self, Read, ReadBytesExt, Write},
+};
+
+/// Append `c` to `buf` in the fixed encoding, exactly as
+/// [`TerminalChar::write_to`] writes it. Unlike the writers, this doesn't
+/// [validate](TerminalChar::validate) the character.
+pub fn encode_cell(c: &TerminalChar, buf: &mut Vec<u8>) {
+    encode_cell_with(c, CharEncoding::Fixed, buf);
+}
+
+/// Append `c` to `buf`, storing the code point as `encoding` specifies.
+pub fn encode_cell_with(c: &TerminalChar, encoding: CharEncoding, buf: &mut Vec<u8>) {
+    let (bytes, len) = encode_array(c, encoding, false);
+    buf.extend_from_slice(&bytes[..len]);
+}
+
+/// Decode the fixed encoded cell at the start of `bytes`, returning it
+/// with the number of bytes it took up.
+///
+/// ## Error
+/// `UnexpectedEof` if `bytes` ends within the cell, `InvalidData` if the
+/// code point isn't a valid `char`
+pub fn decode_cell(bytes: &[u8]) -> io::Result<(TerminalChar, usize)> {
+    decode_cell_with(bytes, CharEncoding::Fixed)
+}
+
+/// Like [`decode_cell`], for a cell written with `encoding`.
+pub fn decode_cell_with(bytes: &[u8], encoding: CharEncoding) -> io::Result<(TerminalChar, usize)> {
+    let mut rest = bytes;
+    let c = read_cell(&mut rest, encoding)?;
+    Ok((c, bytes.len() - rest.len()))
+}
+
+/// Length in bytes of the cell at the start of `bytes`, determined from
+/// the colour flags without decoding anything.
+///
+/// ## Error
+/// `UnexpectedEof` if `bytes` ends within the cell, `InvalidData` if a
+/// varint code point is too long
+pub fn cell_len(bytes: &[u8], encoding: CharEncoding) -> io::Result<usize> {
+    let mut len = match encoding {
+        CharEncoding::Fixed => 4,
+        CharEncoding::Varint => {
+            let end = bytes.iter().take(5).position(|b| b & 0x80 == 0);
+            end.ok_or_else(|| {
+                if bytes.len() < 5 {
+                    io::ErrorKind::UnexpectedEof.into()
+                } else {
+                    io::Error::new(io::ErrorKind::InvalidData, "code point varint too long")
+                }
+            })? + 1
+        }
+    };
+    for _ in 0..2 {
+        let flag = *bytes.get(len).ok_or(io::ErrorKind::UnexpectedEof)?;
+        len += color_len(flag);
+    }
+    if bytes.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(len)
+}
+
+/// Write `c`; without `indexed`, palette indices are stored as their RGB
+/// value for readers that predate them.
+pub(crate) fn write_cell<W: Write>(
+    w: &mut W,
+    c: &TerminalChar,
+    encoding: CharEncoding,
+    indexed: bool,
+) -> io::Result<()> {
+    let (bytes, len) = encode_array(c, encoding, indexed);
+    w.write_all(&bytes[..len])
+}
+
+/// Number of bytes of a colour starting with `flag`, the flag included.
+pub(crate) fn color_len(flag: u8) -> usize {
+    match flag {
+        1 => 4,
+        2 => 2,
+        _ => 1,
+    }
+}
+
+pub(crate) fn read_cell<R: Read>(r: &mut R, encoding: CharEncoding) -> io::Result<TerminalChar> {
+    let code = match encoding {
+        CharEncoding::Fixed => r.read_u32::<LittleEndian>()?,
+        CharEncoding::Varint => read_varint(r)?,
+    };
+    let chr = char::from_u32(code).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "invalid Unicode scalar value")
+    })?;
+    let fg_color = read_color(r)?;
+    let bg_color = read_color(r)?;
+    Ok(TerminalChar {
+        chr,
+        fg_color,
+        bg_color,
+    })
+}
+
+/// Encode `c` into a buffer on the stack, returning it with the number of
+/// bytes used.
+fn encode_array(
+    c: &TerminalChar,
+    encoding: CharEncoding,
+    indexed: bool,
+) -> ([u8; TerminalChar::SERIALIZED_SIZE], usize) {
+    let mut out = [0; TerminalChar::SERIALIZED_SIZE];
+    let mut len = 0;
+    let mut push = |b: u8| {
+        out[len] = b;
+        len += 1;
+    };
+
+    let code = c.chr as u32;
+    match encoding {
+        CharEncoding::Fixed => code.to_le_bytes().into_iter().for_each(&mut push),
+        CharEncoding::Varint => {
+            // a char has at most 21 bits, so this takes at most 3 bytes
+            let mut code = code;
+            while code >= 0x80 {
+                push(code as u8 | 0x80);
+                code >>= 7;
+            }
+            push(code as u8);
+        }
+    }
+    for col in [c.fg_color, c.bg_color] {
+        let exact = col.and_then(|col| col.ansi256()).filter(|_| indexed);
+        match (col.filter(|col| !col.reset), exact) {
+            (Some(_), Some(code)) => [2, code].into_iter().for_each(&mut push),
+            (Some(col), None) => {
+                let (r, g, b) = col.rgb;
+                [1, r, g, b].into_iter().for_each(&mut push);
+            }
+            (None, _) => push(0),
+        }
+    }
+    (out, len)
+}
+
+fn read_color<R: Read>(r: &mut R) -> io::Result<Option<Color>> {
+    match r.read_u8()? {
+        1 => {
+            let mut rgb = [0; 3];
+            r.read_exact(&mut rgb)?;
+            Ok(Some(Color::rgb(rgb[0], rgb[1], rgb[2])))
+        }
+        2 => Ok(Some(Color::ansi256_exact(r.read_u8()?))),
+        _ => Ok(None),
+    }
+}
+
+/// Read a LEB128 encoded `u32`.
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut code = 0u64;
+    for i in 0..5 {
+        let b = r.read_u8()?;
+        code |= ((b & 0x7F) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            return u32::try_from(code).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid Unicode scalar value")
+            });
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "code point varint too long",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_slices() {
+        let cells = [
+            TerminalChar::with_colors('é', Color::Red, Color::rgb(1, 2, 3)),
+            TerminalChar::with_fg('x', Color::Reset),
+            TerminalChar::from('😀'),
+            TerminalChar::with_colors('#', Color::ansi256_exact(208), Color::rgb(4, 5, 6)),
+        ];
+        for encoding in [CharEncoding::Fixed, CharEncoding::Varint] {
+            let mut buf = Vec::new();
+            for c in &cells {
+                encode_cell_with(c, encoding, &mut buf);
+            }
+
+            let mut written = Vec::new();
+            for c in &cells {
+                c.write_encoded(&mut written, encoding).unwrap();
+            }
+            assert_eq!(buf, written);
+
+            let mut rest = &buf[..];
+            for c in &cells {
+                let (decoded, len) = decode_cell_with(rest, encoding).unwrap();
+                // without a header palette indices are stored as RGB
+                let plain =
+                    |col: Option<Color>| col.map(|col| Color::rgb(col.rgb.0, col.rgb.1, col.rgb.2));
+                let expected = c.normalized();
+                assert_eq!(decoded.fg_color, plain(expected.fg_color));
+                assert_eq!(decoded.bg_color, plain(expected.bg_color));
+                assert_eq!(decoded.chr, expected.chr);
+                assert_eq!(len, cell_len(rest, encoding).unwrap());
+                assert_eq!(len, c.serialized_size(encoding));
+                rest = &rest[len..];
+            }
+            assert!(rest.is_empty());
+        }
+
+        let mut buf = Vec::new();
+        encode_cell(&cells[0], &mut buf);
+        assert_eq!(buf.len(), TerminalChar::SERIALIZED_SIZE);
+        let err = decode_cell(&buf[..buf.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            cell_len(&buf[..5], CharEncoding::Fixed).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        buf[..4].copy_from_slice(&0xD800u32.to_le_bytes());
+        assert_eq!(
+            decode_cell(&buf).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
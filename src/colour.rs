@@ -1,18 +1,67 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use core::ops::{Add, Mul, Sub};
+
+/// RGB values of the 16 standard ANSI colours, in code order.
+const ANSI16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel value of a level (0-5) of the 6x6x6 colour cube.
+const fn cube_level(v: u8) -> u8 {
+    if v == 0 { 0 } else { 55 + v * 40 }
+}
+
+/// A terminal colour: an RGB value or the terminal's default.
+///
+/// All fields take part in equality, so equal colours are always stored as
+/// the same bytes and `Color::ansi256_exact(9) != Color::Red`. Compare the
+/// `rgb` fields to ignore the palette index.
+///
+/// Since version 2.5 the struct has an `ansi256` field; struct literals
+/// written before then need `ansi256: None` added, or can use
+/// [`Color::rgb`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
     /// True if this colour is a *Reset* command.
     pub reset: bool,
 
     /// RGB triple — always stored in truecolor space.
     pub rgb: (u8, u8, u8),
+
+    /// The exact ANSI-256 palette index the colour was created from, see
+    /// [`Color::ansi256_exact`]. Nearest-match conversion can't always
+    /// recover it from `rgb`, because several indices share a colour and
+    /// some palettes differ from the one this crate assumes.
+    ///
+    /// Only used while it names `rgb`; read it through [`Color::ansi256`].
+    pub ansi256: Option<u8>,
 }
 
+#[allow(non_upper_case_globals)]
 impl Color {
     /// Reset to terminal defaults.
+    ///
+    /// Its `rgb` is black, but it never equals a colour made from RGB
+    /// values, [`Color::Black`] included.
     pub const fn reset() -> Self {
         Self {
             reset: true,
             rgb: (0, 0, 0),
+            ansi256: None,
         }
     }
 
@@ -21,18 +70,38 @@ impl Color {
         Self {
             reset: false,
             rgb: (r, g, b),
+            ansi256: None,
         }
     }
 
-    /// Create from an ANSI-256 code.
-    pub fn from_ansi256(code: u8) -> Self {
+    /// Create from an ANSI-256 code, keeping only its RGB value.
+    pub const fn from_ansi256(code: u8) -> Self {
+        let (r, g, b) = Self::ansi256_to_rgb(code);
+        Self::rgb(r, g, b)
+    }
+
+    /// Create from an ANSI-256 code and remember it, so [`Color::as_ansi256`]
+    /// returns exactly `code` and files store the index itself.
+    ///
+    /// Colours computed from this one, by blending or scaling for example,
+    /// are plain RGB colours again.
+    pub const fn ansi256_exact(code: u8) -> Self {
         Self {
-            reset: false,
-            rgb: Self::ansi256_to_rgb(code),
+            ansi256: Some(code),
+            ..Self::from_ansi256(code)
         }
     }
 
-    /// Convert self to an ANSI-256 index (best match).
+    /// The palette index remembered by [`Color::ansi256_exact`], `None` for
+    /// other colours, reset ones, and if `rgb` was changed since, so that it
+    /// no longer matches the index.
+    pub fn ansi256(&self) -> Option<u8> {
+        self.ansi256
+            .filter(|&code| !self.reset && Self::ansi256_to_rgb(code) == self.rgb)
+    }
+
+    /// Convert self to an ANSI-256 index: the remembered one of a colour
+    /// from [`Color::ansi256_exact`], otherwise the best match.
     ///
     /// ## return
     /// self converted to an ANSI-256 index, or `None` if it is a reset color.
@@ -40,40 +109,45 @@ impl Color {
         if self.reset {
             return None;
         }
-        Some(Self::rgb_to_ansi256(self.rgb.0, self.rgb.1, self.rgb.2))
+        Some(
+            self.ansi256()
+                .unwrap_or_else(|| Self::rgb_to_ansi256(self.rgb.0, self.rgb.1, self.rgb.2)),
+        )
+    }
+
+    /// Create from one of the 16 standard ANSI colour codes.
+    /// Only the lower 4 bits of `code` are used.
+    pub const fn from_ansi16(code: u8) -> Self {
+        let (r, g, b) = ANSI16[(code & 0x0F) as usize];
+        Self::rgb(r, g, b)
+    }
+
+    /// Convert self to the nearest of the 16 standard ANSI colours.
+    ///
+    /// ## return
+    /// a code in `0..=15`, or `None` if it is a reset color.
+    pub fn as_ansi16(self) -> Option<u8> {
+        if self.reset {
+            return None;
+        }
+        let (r, g, b) = self.rgb;
+        (0..16u8).min_by_key(|&code| {
+            let (cr, cg, cb) = ANSI16[code as usize];
+            Self::color_dist(r, g, b, cr, cg, cb)
+        })
     }
 
     /// Convert ANSI-256 -> RGB
-    pub fn ansi256_to_rgb(code: u8) -> (u8, u8, u8) {
+    pub const fn ansi256_to_rgb(code: u8) -> (u8, u8, u8) {
         match code {
-            0..=15 => {
-                let table = [
-                    (0, 0, 0),
-                    (128, 0, 0),
-                    (0, 128, 0),
-                    (128, 128, 0),
-                    (0, 0, 128),
-                    (128, 0, 128),
-                    (0, 128, 128),
-                    (192, 192, 192),
-                    (128, 128, 128),
-                    (255, 0, 0),
-                    (0, 255, 0),
-                    (255, 255, 0),
-                    (0, 0, 255),
-                    (255, 0, 255),
-                    (0, 255, 255),
-                    (255, 255, 255),
-                ];
-                table[code as usize]
-            }
+            0..=15 => ANSI16[code as usize],
             16..=231 => {
                 let c = code - 16;
-                let r = c / 36;
-                let g = (c % 36) / 6;
-                let b = c % 6;
-                let to_rgb = |v| if v == 0 { 0 } else { 55 + v * 40 };
-                (to_rgb(r), to_rgb(g), to_rgb(b))
+                (
+                    cube_level(c / 36),
+                    cube_level((c % 36) / 6),
+                    cube_level(c % 6),
+                )
             }
             232..=255 => {
                 let gray = 8 + (code - 232) * 10;
@@ -124,7 +198,7 @@ impl Color {
         let gray_index = if avg > 238 {
             23
         } else {
-            ((avg - 3) / 10).min(23)
+            (avg.saturating_sub(3) / 10).min(23)
         };
         let gray_code = 232 + gray_index as u8;
 
@@ -140,10 +214,475 @@ impl Color {
         }
     }
 
-    fn color_dist(r1: u8, g1: u8, b1: u8, r2: u8, g2: u8, b2: u8) -> u32 {
+    /// Relative luminance as defined by WCAG, from 0.0 (black) to 1.0 (white).
+    ///
+    /// Reset colours carry no real RGB value and are measured as black.
+    #[cfg(feature = "std")]
+    pub fn relative_luminance(self) -> f32 {
+        let channel = |v: u8| {
+            let c = v as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = if self.reset { (0, 0, 0) } else { self.rgb };
+        0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+    }
+
+    /// WCAG contrast ratio between two colours, from 1.0 (identical) to 21.0 (black on white).
+    /// The order of the colours doesn't matter.
+    #[cfg(feature = "std")]
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Pick black or white text, whichever is more readable on `bg`.
+    #[cfg(feature = "std")]
+    pub fn best_text_color(bg: Color) -> Color {
+        if Self::Black.contrast_ratio(&bg) >= Self::White.contrast_ratio(&bg) {
+            Self::Black
+        } else {
+            Self::White
+        }
+    }
+
+    /// Linear interpolation towards `other`: `t = 0.0` gives `self`,
+    /// `t = 1.0` gives `other`. `t` is clamped to that range.
+    ///
+    /// A reset colour has no RGB value to mix, so blending with one returns
+    /// the other colour unchanged.
+    pub fn blend(self, other: Color, t: f32) -> Color {
+        match (self.reset, other.reset) {
+            (true, _) => other,
+            (_, true) => self,
+            _ => {
+                let t = t.clamp(0.0, 1.0);
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u8;
+                let (r1, g1, b1) = self.rgb;
+                let (r2, g2, b2) = other.rgb;
+                Color::rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+            }
+        }
+    }
+
+    /// Multiply the channels of both colours, darkening like a filter:
+    /// white leaves a colour unchanged, black turns it black.
+    /// Multiplying with a reset colour returns the other one unchanged.
+    pub fn multiply(self, other: Color) -> Color {
+        match (self.reset, other.reset) {
+            (true, _) => other,
+            (_, true) => self,
+            _ => {
+                let mul = |a: u8, b: u8| ((a as u16 * b as u16 + 127) / 255) as u8;
+                let (r1, g1, b1) = self.rgb;
+                let (r2, g2, b2) = other.rgb;
+                Color::rgb(mul(r1, r2), mul(g1, g2), mul(b1, b2))
+            }
+        }
+    }
+
+    /// Multiply every channel by `factor`, saturating at 255. Factors of
+    /// zero or below give black, reset colours are returned unchanged.
+    pub fn scale_brightness(self, factor: f32) -> Color {
+        if self.reset {
+            return self;
+        }
+        let factor = factor.max(0.0);
+        let scale = |v: u8| (v as f32 * factor + 0.5).min(255.0) as u8;
+        let (r, g, b) = self.rgb;
+        Color::rgb(scale(r), scale(g), scale(b))
+    }
+
+    /// Filter the colour through the light of a black body at `kelvin`,
+    /// clamped to 1000–40000K: low temperatures warm it towards orange, high
+    /// ones cool it towards blue, and around 6600K it stays about the same.
+    /// Reset colours are returned unchanged.
+    #[cfg(feature = "std")]
+    pub fn apply_temperature(self, kelvin: f32) -> Color {
+        if self.reset {
+            return self;
+        }
+        let (r, g, b) = blackbody(kelvin);
+        self.multiply(Color::rgb(r, g, b))
+    }
+
+    /// Index of the palette entry closest to `self` under `metric`, the
+    /// first one on a tie. Reset entries are skipped.
+    ///
+    /// ## return
+    /// `None` if `self` is a reset colour or the palette has no RGB colour
+    pub fn nearest_in(&self, palette: &[Color], metric: DistanceMetric) -> Option<usize> {
+        if self.reset {
+            return None;
+        }
+        palette
+            .iter()
+            .enumerate()
+            .filter(|(_, col)| !col.reset)
+            .map(|(i, col)| (i, metric.distance(self.rgb, col.rgb)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Squared Euclidean distance of two RGB colours, the metric the
+    /// crate's own ANSI conversions use.
+    pub fn color_dist(r1: u8, g1: u8, b1: u8, r2: u8, g2: u8, b2: u8) -> u32 {
         let dr = r1 as i32 - r2 as i32;
         let dg = g1 as i32 - g2 as i32;
         let db = b1 as i32 - b2 as i32;
         (dr * dr + dg * dg + db * db) as u32
     }
 }
+
+/// How [`Color::nearest_in`] measures how different two colours are.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DistanceMetric {
+    /// Straight-line distance in RGB, see [`Color::color_dist`].
+    #[default]
+    Euclidean,
+    /// The "redmean" approximation: RGB distance with the channels weighted
+    /// by the average red, a cheap way to follow human perception better.
+    Redmean,
+    /// Distance in the CIE L*a*b* colour space (ΔE 1976), designed so equal
+    /// distances look equally different. The slowest of the metrics.
+    Cielab,
+}
+
+impl DistanceMetric {
+    /// A value that grows with the distance, only meant for comparisons.
+    fn distance(self, a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+        match self {
+            DistanceMetric::Euclidean => Color::color_dist(a.0, a.1, a.2, b.0, b.1, b.2) as f32,
+            DistanceMetric::Redmean => {
+                let mean = (a.0 as f32 + b.0 as f32) / 2.0;
+                let dr = a.0 as f32 - b.0 as f32;
+                let dg = a.1 as f32 - b.1 as f32;
+                let db = a.2 as f32 - b.2 as f32;
+                (2.0 + mean / 256.0) * dr * dr
+                    + 4.0 * dg * dg
+                    + (2.0 + (255.0 - mean) / 256.0) * db * db
+            }
+            DistanceMetric::Cielab => {
+                let (l1, a1, b1) = lab(a);
+                let (l2, a2, b2) = lab(b);
+                let (dl, da, db) = (l1 - l2, a1 - a2, b1 - b2);
+                dl * dl + da * da + db * db
+            }
+        }
+    }
+}
+
+/// The `n`th root of a non-negative `v`, by Newton's method so that
+/// [`lab`] works without `std`.
+fn root(v: f32, n: u32) -> f32 {
+    if v <= 0.0 {
+        return 0.0;
+    }
+    // starting above the root, every step moves down towards it
+    let mut y = v.max(1.0);
+    for _ in 0..64 {
+        let pow = (1..n).fold(1.0, |acc, _| acc * y);
+        let next = ((n - 1) as f32 * y + v / pow) / n as f32;
+        if next >= y {
+            break;
+        }
+        y = next;
+    }
+    y
+}
+
+/// Convert an sRGB colour to CIE L*a*b* under the D65 white point.
+fn lab((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+    let linear = |v: u8| {
+        let c = v as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            // c^2.4 as c^2 times the fifth root of c^2
+            let s = (c + 0.055) / 1.055;
+            s * s * root(s * s, 5)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    // XYZ relative to the D65 white
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+    let f = |t: f32| {
+        if t > 216.0 / 24389.0 {
+            root(t, 3)
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+/// Approximate colour of a black body radiating at `kelvin`, after
+/// Tanner Helland's fit of the CIE 1964 colour matching data.
+#[cfg(feature = "std")]
+fn blackbody(kelvin: f32) -> (u8, u8, u8) {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (t - 60.0).powf(-0.13320476)
+    };
+    let g = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.07551485)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+    let channel = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Channel-wise saturating addition, so `Color::Red + Color::Lime` is
+/// yellow. Like [`Color::multiply`], adding a reset colour returns the other
+/// one unchanged.
+impl Add for Color {
+    type Output = Color;
+
+    fn add(self, other: Color) -> Color {
+        self.zip_channels(other, u8::saturating_add)
+    }
+}
+
+/// Channel-wise saturating subtraction, clamped at 0. Subtracting a reset
+/// colour, or from one, returns the other one unchanged.
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(self, other: Color) -> Color {
+        self.zip_channels(other, u8::saturating_sub)
+    }
+}
+
+/// Scale every channel, see [`Color::scale_brightness`].
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, factor: f32) -> Color {
+        self.scale_brightness(factor)
+    }
+}
+
+impl Color {
+    fn zip_channels(self, other: Color, op: fn(u8, u8) -> u8) -> Color {
+        match (self.reset, other.reset) {
+            (true, _) => other,
+            (_, true) => self,
+            _ => {
+                let (r1, g1, b1) = self.rgb;
+                let (r2, g2, b2) = other.rgb;
+                Color::rgb(op(r1, r2), op(g1, g2), op(b1, b2))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [(u8, u8, u8); 256] = {
+        let mut table = [(0, 0, 0); 256];
+        let mut i = 0;
+        while i < 256 {
+            table[i] = Color::ansi256_to_rgb(i as u8);
+            i += 1;
+        }
+        table
+    };
+
+    #[test]
+    fn test_ansi16_conversion() {
+        assert_eq!(Color::Teal.as_ansi16(), Some(6));
+        assert_eq!(Color::rgb(250, 10, 5).as_ansi16(), Some(9));
+        assert_eq!(Color::rgb(200, 200, 190).as_ansi16(), Some(7));
+        assert_eq!(Color::Reset.as_ansi16(), None);
+        for code in 0..16 {
+            assert_eq!(Color::from_ansi16(code).as_ansi16(), Some(code));
+        }
+    }
+
+    #[test]
+    fn test_rgb_to_ansi256_near_black() {
+        // the grey ramp lookup used to underflow for averages below 3
+        assert_eq!(Color::rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(Color::rgb_to_ansi256(0, 0, 1), 16);
+        assert_eq!(Color::rgb_to_ansi256(8, 8, 8), 232);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((Color::Black.contrast_ratio(&Color::White) - 21.0).abs() < 0.01);
+        assert!((Color::Red.contrast_ratio(&Color::Red) - 1.0).abs() < f32::EPSILON);
+        assert_eq!(
+            Color::Navy.contrast_ratio(&Color::Yellow),
+            Color::Yellow.contrast_ratio(&Color::Navy)
+        );
+        assert_eq!(Color::best_text_color(Color::Yellow), Color::Black);
+        assert_eq!(Color::best_text_color(Color::Navy), Color::White);
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(
+            Color::Black.blend(Color::White, 0.5),
+            Color::rgb(128, 128, 128)
+        );
+        assert_eq!(Color::Red.blend(Color::Blue, 0.0), Color::Red);
+        assert_eq!(Color::Red.blend(Color::Blue, 2.0), Color::Blue);
+        assert_eq!(Color::Reset.blend(Color::Lime, 0.25), Color::Lime);
+        assert_eq!(Color::Lime.blend(Color::Reset, 0.75), Color::Lime);
+
+        assert_eq!(Color::Red.multiply(Color::White), Color::Red);
+        assert_eq!(Color::Red.multiply(Color::Black), Color::Black);
+        assert_eq!(
+            Color::rgb(200, 100, 50).multiply(Color::Grey),
+            Color::rgb(100, 50, 25)
+        );
+        assert_eq!(Color::Reset.multiply(Color::Navy), Color::Navy);
+    }
+
+    #[test]
+    fn test_nearest_in() {
+        let palette = [
+            Color::Reset,
+            Color::Black,
+            Color::Red,
+            Color::Lime,
+            Color::Navy,
+        ];
+        let nearest = |c: Color, metric| c.nearest_in(&palette, metric);
+        assert_eq!(
+            nearest(Color::rgb(200, 30, 20), DistanceMetric::Euclidean),
+            Some(2)
+        );
+        assert_eq!(
+            nearest(Color::rgb(10, 10, 10), DistanceMetric::Redmean),
+            Some(1)
+        );
+        assert_eq!(nearest(Color::Black, DistanceMetric::Euclidean), Some(1));
+        assert_eq!(nearest(Color::Reset, DistanceMetric::Euclidean), None);
+        assert_eq!(
+            Color::Red.nearest_in(&[Color::Reset], DistanceMetric::Redmean),
+            None
+        );
+        assert_eq!(Color::Red.nearest_in(&[], DistanceMetric::Euclidean), None);
+
+        assert_eq!(Color::color_dist(0, 0, 0, 3, 4, 0), 25);
+    }
+
+    #[test]
+    fn test_cielab_distance() {
+        let palette = [Color::rgb(128, 128, 128), Color::rgb(0, 0, 255)];
+        // a saturated violet is perceptually closer to blue than to grey
+        let violet = Color::rgb(90, 40, 200);
+        assert_eq!(violet.nearest_in(&palette, DistanceMetric::Cielab), Some(1));
+        assert_eq!(
+            Color::White.nearest_in(&palette, DistanceMetric::Cielab),
+            Some(0)
+        );
+        let (l, a, b) = lab((255, 255, 255));
+        assert!((l - 100.0).abs() < 0.05 && a.abs() < 0.05 && b.abs() < 0.05);
+        assert!((root(0.125, 3) - 0.5).abs() < 1e-6);
+        assert!((root(0.001, 5) - 0.251_188_6).abs() < 1e-6);
+        assert!((root(8.0, 3) - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ansi256_exact() {
+        // maroon is palette index 1, but the nearest match is the cube's 88
+        assert_eq!(Color::from_ansi256(1).as_ansi256(), Some(88));
+        let exact = Color::ansi256_exact(1);
+        assert_eq!(exact.as_ansi256(), Some(1));
+        assert_eq!(exact.rgb, Color::Maroon.rgb);
+        assert_ne!(exact, Color::Maroon);
+        assert_eq!(exact.ansi256(), Some(1));
+        assert_eq!(Color::Maroon.ansi256(), None);
+        assert_eq!(Color::ansi256_exact(16).as_ansi256(), Some(16));
+        assert_eq!(exact.blend(Color::Maroon, 0.5).ansi256(), None);
+        assert_eq!((exact * 1.0).ansi256(), None);
+
+        // the index is part of the value
+        #[cfg(feature = "std")]
+        {
+            let set: std::collections::HashSet<_> = [Color::ansi256_exact(9), Color::Red].into();
+            assert_eq!(set.len(), 2);
+        }
+        // changing the RGB value drops the index
+        let mut edited = Color::ansi256_exact(1);
+        edited.rgb = (255, 0, 0);
+        assert_eq!(edited.ansi256(), None);
+        assert_eq!(edited.as_ansi256(), Some(196));
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(Color::Red + Color::Lime, Color::Yellow);
+        assert_eq!(
+            Color::rgb(200, 100, 0) + Color::rgb(100, 100, 5),
+            Color::rgb(255, 200, 5)
+        );
+        assert_eq!(
+            Color::rgb(200, 100, 0) - Color::rgb(100, 150, 5),
+            Color::rgb(100, 0, 0)
+        );
+        assert_eq!(Color::White - Color::White, Color::Black);
+        assert_eq!(Color::Reset + Color::Navy, Color::Navy);
+        assert_eq!(Color::Navy - Color::Reset, Color::Navy);
+        assert_eq!(Color::Reset - Color::Reset, Color::Reset);
+        assert_eq!(Color::rgb(200, 100, 10) * 0.5, Color::rgb(100, 50, 5));
+        assert_eq!(Color::rgb(200, 100, 10) * 2.0, Color::rgb(255, 200, 20));
+        assert_eq!(Color::White * -1.0, Color::Black);
+        assert_eq!(Color::Reset * 0.5, Color::Reset);
+    }
+
+    #[test]
+    fn test_scale_brightness() {
+        let c = Color::rgb(200, 100, 10);
+        assert_eq!(c.scale_brightness(0.5), Color::rgb(100, 50, 5));
+        assert_eq!(c.scale_brightness(2.0), Color::rgb(255, 200, 20));
+        assert_eq!(c.scale_brightness(0.0), Color::Black);
+        assert_eq!(c.scale_brightness(-3.0), Color::Black);
+        assert_eq!(c.scale_brightness(f32::NAN), Color::Black);
+        assert_eq!(Color::Reset.scale_brightness(0.5), Color::Reset);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_apply_temperature() {
+        let grey = Color::rgb(200, 200, 200);
+        let (r, g, b) = grey.apply_temperature(2000.0).rgb;
+        assert!(r == 200 && g < 150 && b < 50, "{:?}", (r, g, b));
+        let (r, g, b) = grey.apply_temperature(15000.0).rgb;
+        assert!(r < 150 && g < 200 && b == 200, "{:?}", (r, g, b));
+        let (r, g, b) = grey.apply_temperature(6600.0).rgb;
+        assert!(r >= 195 && g >= 195 && b >= 195, "{:?}", (r, g, b));
+        assert_eq!(Color::Reset.apply_temperature(2000.0), Color::Reset);
+    }
+
+    #[test]
+    fn test_const_ansi256_palette() {
+        assert_eq!(PALETTE[9], Color::Red.rgb);
+        assert_eq!(PALETTE[16], (0, 0, 0));
+        assert_eq!(PALETTE[231], (255, 255, 255));
+        assert_eq!(PALETTE[196], (255, 0, 0));
+        assert_eq!(PALETTE[244], (128, 128, 128));
+    }
+}
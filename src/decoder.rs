@@ -0,0 +1,199 @@
+use alloc::vec::Vec;
+
+use crate::{AsciiSprite, AsciiVideo, FormatInfo, ReadLimits, cell_count, codec, header, io};
+
+/// Decodes a video from bytes that arrive in pieces, e.g. from a socket in
+/// an async runtime, without blocking on a reader.
+///
+/// [`AsciiVideoDecoder::push`] takes whatever bytes are available and
+/// decodes every frame they complete; nothing waits for more input. The
+/// header and frames are validated exactly as by [`AsciiVideo::read_from`],
+/// and bytes are only kept until the frame they belong to is decoded.
+#[derive(Debug, Default)]
+pub struct AsciiVideoDecoder {
+    limits: ReadLimits,
+    buf: Vec<u8>,
+    header: Option<(FormatInfo, header::Extensions)>,
+    frames: Vec<AsciiSprite>,
+    /// Cells of the next frame found complete in `buf` so far, and the
+    /// bytes they take up, so that no cell is scanned twice.
+    scanned: (usize, usize),
+}
+
+impl AsciiVideoDecoder {
+    /// A decoder checking the header against [`ReadLimits::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A decoder rejecting videos that exceed `limits`, like
+    /// [`AsciiVideo::read_from_with_limits`].
+    pub fn with_limits(limits: ReadLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Add the next `bytes` of the video and decode what they complete.
+    ///
+    /// Bytes after the last frame are ignored, as by the other readers.
+    ///
+    /// ## Error
+    /// if the header or a frame is invalid. The decoder is of no further
+    /// use then.
+    pub fn push(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.buf.extend_from_slice(bytes);
+        if self.header.is_none() {
+            let mut r = self.buf.as_slice();
+            match AsciiVideo::read_header(&mut r, self.limits) {
+                Ok(header) => {
+                    let used = self.buf.len() - r.len();
+                    self.buf.drain(..used);
+                    self.header = Some(header);
+                }
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+        let Some((info, ext)) = &self.header else {
+            unreachable!("the header was just read");
+        };
+
+        let cells = cell_count(info.width, info.height, io::ErrorKind::InvalidData)?;
+        let mut start = 0;
+        while self.frames.len() < info.frame_count {
+            let (done, len) = &mut self.scanned;
+            while *done < cells {
+                match codec::cell_len(&self.buf[start + *len..], ext.char_encoding) {
+                    Ok(cell) => {
+                        *done += 1;
+                        *len += cell;
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                    Err(e) => return Err(e),
+                }
+            }
+            if *done < cells {
+                break;
+            }
+            let end = start + *len;
+            self.frames.push(AsciiSprite::read_encoded(
+                &mut &self.buf[start..end],
+                info.width,
+                info.height,
+                ext.char_encoding,
+            )?);
+            self.scanned = (0, 0);
+            start = end;
+        }
+        if self.is_complete() {
+            self.buf = Vec::new();
+        } else {
+            self.buf.drain(..start);
+        }
+        Ok(())
+    }
+
+    /// The header, once enough bytes have arrived to read it.
+    pub fn info(&self) -> Option<&FormatInfo> {
+        self.header.as_ref().map(|(info, _)| info)
+    }
+
+    /// Number of frames decoded so far.
+    pub fn frames_decoded(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// True once the header and all the frames it declares are decoded.
+    pub fn is_complete(&self) -> bool {
+        self.info()
+            .is_some_and(|info| self.frames.len() == info.frame_count)
+    }
+
+    /// The decoded video.
+    ///
+    /// ## Error
+    /// `UnexpectedEof` if the video isn't complete yet
+    pub fn finish(self) -> io::Result<AsciiVideo> {
+        if !self.is_complete() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "video ended before its last frame",
+            ));
+        }
+        let Some((info, ext)) = self.header else {
+            unreachable!("a complete decoder has read the header");
+        };
+        let mut video = AsciiVideo::new_unlimited(info.width, info.height, self.frames)?;
+        video.set_extensions(ext);
+        Ok(video)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharEncoding, Color, TerminalChar};
+    use alloc::vec;
+
+    #[test]
+    fn test_decoder_matches_read_from() {
+        let frames = (0..5u8)
+            .map(|i| {
+                let pixels = vec![
+                    TerminalChar::with_fg('é', Color::rgb(i, 2, 3)),
+                    TerminalChar::from(char::from(b'a' + i)),
+                    TerminalChar::with_colors('#', Color::ansi256_exact(i), Color::Navy),
+                ];
+                AsciiSprite::new(3, 1, pixels).unwrap()
+            })
+            .collect();
+        let mut video = AsciiVideo::new(3, 1, frames).unwrap();
+        video.frame_rate = Some(12.0);
+        video.set_label(2, "hit").unwrap();
+
+        for encoding in [CharEncoding::Fixed, CharEncoding::Varint] {
+            video.char_encoding = encoding;
+            let bytes = video.to_bytes().unwrap();
+            for chunk in [1, 5, 64, bytes.len()] {
+                let mut decoder = AsciiVideoDecoder::new();
+                for (i, piece) in bytes.chunks(chunk).enumerate() {
+                    assert!(!decoder.is_complete() || i * chunk >= bytes.len());
+                    decoder.push(piece).unwrap();
+                }
+                assert_eq!(decoder.frames_decoded(), 5);
+                assert_eq!(decoder.info().map(|info| info.frame_count), Some(5));
+                assert_eq!(decoder.finish().unwrap(), video);
+            }
+
+            let mut decoder = AsciiVideoDecoder::new();
+            decoder.push(&bytes[..bytes.len() - 1]).unwrap();
+            assert_eq!(decoder.frames_decoded(), 4);
+            let err = decoder.finish().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn test_decoder_validation() {
+        let frame = AsciiSprite::new(4, 2, vec![TerminalChar::from('x'); 8]).unwrap();
+        let bytes = AsciiVideo::new(4, 2, vec![frame; 2])
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let limits = ReadLimits {
+            max_width: 3,
+            ..ReadLimits::default()
+        };
+        let sync = AsciiVideo::read_from_with_limits(&mut bytes.as_slice(), limits);
+        let mut decoder = AsciiVideoDecoder::with_limits(limits);
+        let err = bytes
+            .chunks(3)
+            .find_map(|piece| decoder.push(piece).err())
+            .expect("the decoder accepted a video wider than the limit");
+        assert_eq!(err.kind(), sync.unwrap_err().kind());
+        assert_eq!(decoder.info(), None);
+    }
+}
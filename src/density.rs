@@ -0,0 +1,113 @@
+//! Glyph density: how much of its cell a character covers with ink.
+
+use alloc::vec::Vec;
+
+use crate::{AsciiSprite, width::char_width};
+
+/// Approximate share of the cell covered by the printable ASCII characters
+/// `'!'..='~'` in a typical monospace font, where a full block `█` is 1.0.
+const ASCII_COVERAGE: [f32; 94] = [
+    0.12, 0.10, 0.42, 0.38, 0.44, 0.40, 0.05, 0.14, 0.14, 0.22, 0.17, 0.07, 0.09, 0.04,
+    0.13, // !../
+    0.36, 0.22, 0.30, 0.30, 0.32, 0.32, 0.34, 0.24, 0.38, 0.34, // 0..9
+    0.08, 0.11, 0.14, 0.16, 0.14, 0.22, 0.48, // :..@
+    0.36, 0.42, 0.30, 0.38, 0.36, 0.29, 0.37, 0.38, 0.24, 0.24, 0.35, 0.22, 0.44, // A..M
+    0.42, 0.38, 0.32, 0.42, 0.39, 0.33, 0.24, 0.35, 0.31, 0.45, 0.35, 0.27, 0.33, // N..Z
+    0.18, 0.13, 0.18, 0.10, 0.08, 0.04, // [..`
+    0.32, 0.34, 0.24, 0.34, 0.32, 0.24, 0.38, 0.30, 0.17, 0.19, 0.29, 0.18, 0.36, // a..m
+    0.26, 0.28, 0.34, 0.34, 0.17, 0.25, 0.21, 0.26, 0.22, 0.34, 0.25, 0.28, 0.25, // n..z
+    0.18, 0.12, 0.18, 0.10, // {..~
+];
+
+/// Approximate coverage of `c`. Whitespace and zero-width characters have
+/// none; characters without a better estimate count as half covered.
+pub(crate) fn coverage(c: char) -> f32 {
+    match c {
+        '!'..='~' => ASCII_COVERAGE[c as usize - '!' as usize],
+        '█' => 1.0,
+        '▓' => 0.75,
+        '▒' => 0.5,
+        '░' => 0.25,
+        '▀' | '▐' => 0.5,
+        // lower eighths `▁..▇`, then left eighths `▉..▏`
+        '▁'..='▇' => (c as u32 - 0x2580) as f32 / 8.0,
+        '▉'..='▏' => (0x2590 - c as u32) as f32 / 8.0,
+        // quadrants
+        '▖' | '▗' | '▘' | '▝' => 0.25,
+        '▚' | '▞' => 0.5,
+        '▙' | '▛' | '▜' | '▟' => 0.75,
+        // braille, one eighth per raised dot
+        '\u{2800}'..='\u{28FF}' => (c as u32 - 0x2800).count_ones() as f32 / 8.0,
+        // box drawing, apart from the light lines
+        '─' | '│' | '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => {
+            0.15
+        }
+        '\u{2500}'..='\u{257F}' => 0.25,
+        _ if c.is_whitespace() || char_width(c) == 0 => 0.0,
+        _ => 0.5,
+    }
+}
+
+impl AsciiSprite {
+    /// The "ink" of every cell in row-major order: how much of the cell its
+    /// character covers, from 0.0 for spaces to 1.0 for a full block `█`.
+    ///
+    /// The values come from a small table of typical glyph shapes in
+    /// monospace fonts, so they are estimates, good for comparing cells.
+    /// Only the character counts, colours are ignored.
+    pub fn density_map(&self) -> Vec<f32> {
+        self.pixels.iter().map(|c| coverage(c.chr)).collect()
+    }
+
+    /// Centre of mass of the [`AsciiSprite::density_map`], as `(x, y)` in
+    /// cells, where every cell's ink sits at the cell's centre. A sprite
+    /// that is symmetric in its ink has its centre of mass at
+    /// `(width / 2, height / 2)`, which is also the result for a sprite
+    /// without any ink.
+    pub fn center_of_mass(&self) -> (f32, f32) {
+        let (mut total, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0);
+        for ((x, y, _), ink) in self.enumerate_cells().zip(self.density_map()) {
+            total += ink;
+            sum_x += ink * (x as f32 + 0.5);
+            sum_y += ink * (y as f32 + 0.5);
+        }
+        if total == 0.0 {
+            return (self.width as f32 / 2.0, self.height as f32 / 2.0);
+        }
+        (sum_x / total, sum_y / total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ramp, TerminalChar, TerminalString};
+    use alloc::vec;
+
+    fn sprite(rows: &[&str]) -> AsciiSprite {
+        AsciiSprite::from_rows(rows.iter().map(|&r| TerminalString::from(r)).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_density_map() {
+        let classic: Vec<f32> = Ramp::classic()
+            .chars()
+            .iter()
+            .map(|&c| coverage(c))
+            .collect();
+        assert!(classic.windows(2).all(|w| w[0] < w[1]), "{:?}", classic);
+        assert_eq!(coverage('⣿'), 1.0);
+        assert_eq!(coverage('\u{301}'), 0.0);
+
+        let corner = sprite(&["█ ", " ."]);
+        assert_eq!(corner.density_map(), [1.0, 0.0, 0.0, coverage('.')]);
+        let (x, y) = corner.center_of_mass();
+        let expected = (0.5 + 1.5 * 0.04) / 1.04;
+        assert!((x - expected).abs() < 1e-6 && (y - expected).abs() < 1e-6);
+
+        let symmetric = sprite(&["#.#", "#.#"]);
+        assert_eq!(symmetric.center_of_mass(), (1.5, 1.0));
+        let blank = AsciiSprite::new(4, 2, vec![TerminalChar::default(); 8]).unwrap();
+        assert_eq!(blank.center_of_mass(), (2.0, 1.0));
+    }
+}
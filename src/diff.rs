@@ -0,0 +1,146 @@
+//! Locating where two sprites or videos differ.
+
+use core::fmt;
+
+use crate::{AsciiSprite, AsciiVideo, TerminalChar};
+
+/// The first difference between two videos, see [`AsciiVideo::first_difference`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameDiff {
+    /// The videos have different dimensions, `(width, height)` each.
+    Dimensions { left: (u16, u16), right: (u16, u16) },
+    /// Frame `frame` differs first at cell `(x, y)`.
+    Cell {
+        frame: usize,
+        x: u16,
+        y: u16,
+        left: TerminalChar,
+        right: TerminalChar,
+    },
+    /// All frames both videos have are equal, but one has more.
+    FrameCount { left: usize, right: usize },
+}
+
+impl fmt::Display for FrameDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameDiff::Dimensions { left, right } => write!(
+                f,
+                "size {}x{} differs from {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+            FrameDiff::Cell {
+                frame,
+                x,
+                y,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {} differs at ({}, {}): {:?} vs {:?}",
+                frame, x, y, left, right
+            ),
+            FrameDiff::FrameCount { left, right } => {
+                write!(f, "{} frames vs {}", left, right)
+            }
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// Position of the first cell, in row-major order, that differs from
+    /// the same cell of `other`, or `None` if all cells are equal. The
+    /// sprites should have the same dimensions, past the end of the
+    /// smaller one nothing is compared.
+    pub fn first_difference(&self, other: &AsciiSprite) -> Option<(u16, u16)> {
+        self.enumerate_cells()
+            .zip(&other.pixels)
+            .find(|((_, _, a), b)| a != b)
+            .map(|((x, y, _), _)| (x, y))
+    }
+}
+
+impl AsciiVideo {
+    /// Where this video first differs from `other`, or `None` if their
+    /// frames are equal: a size mismatch, else the first differing cell of
+    /// the earliest differing frame, else a frame count mismatch.
+    ///
+    /// Only dimensions and frames are compared, unlike `==` the metadata
+    /// and timing don't count. Handy to pinpoint a failing roundtrip:
+    /// `assert_eq!(a.first_difference(&b), None)`.
+    pub fn first_difference(&self, other: &AsciiVideo) -> Option<FrameDiff> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Some(FrameDiff::Dimensions {
+                left: (self.width, self.height),
+                right: (other.width, other.height),
+            });
+        }
+        for (frame, (a, b)) in self.frames.iter().zip(&other.frames).enumerate() {
+            if let Some((x, y)) = a.first_difference(b) {
+                let idx = y as usize * a.width as usize + x as usize;
+                return Some(FrameDiff::Cell {
+                    frame,
+                    x,
+                    y,
+                    left: a.pixels[idx],
+                    right: b.pixels[idx],
+                });
+            }
+        }
+        (self.frames.len() != other.frames.len()).then_some(FrameDiff::FrameCount {
+            left: self.frames.len(),
+            right: other.frames.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    #[test]
+    fn test_first_difference() {
+        let frames: Vec<_> = "abc"
+            .chars()
+            .map(|c| AsciiSprite::new(2, 2, vec![TerminalChar::from(c); 4]).unwrap())
+            .collect();
+        let video = AsciiVideo::new(2, 2, frames.clone()).unwrap();
+        let mut timed = video.clone();
+        timed.frame_rate = Some(5.0);
+        assert_eq!(video.first_difference(&timed), None);
+
+        let mut other = video.clone();
+        let changed = TerminalChar::with_fg('b', Color::Red);
+        other.frames[1].pixels[3] = changed;
+        other.frames[2].pixels[0] = changed;
+        let diff = video.first_difference(&other).unwrap();
+        assert_eq!(
+            diff,
+            FrameDiff::Cell {
+                frame: 1,
+                x: 1,
+                y: 1,
+                left: TerminalChar::from('b'),
+                right: changed,
+            }
+        );
+        assert!(diff.to_string().starts_with("frame 1 differs at (1, 1)"));
+        assert_eq!(frames[1].first_difference(&other.frames[1]), Some((1, 1)));
+
+        let short = AsciiVideo::new(2, 2, frames[..2].to_vec()).unwrap();
+        assert_eq!(
+            video.first_difference(&short),
+            Some(FrameDiff::FrameCount { left: 3, right: 2 })
+        );
+        let small = AsciiVideo::new(1, 2, vec![]).unwrap();
+        assert_eq!(
+            video.first_difference(&small),
+            Some(FrameDiff::Dimensions {
+                left: (2, 2),
+                right: (1, 2)
+            })
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Drawing primitives on sprites.
+//!
+//! Coordinates are signed and may lie outside the sprite: shapes are clipped
+//! to the sprite bounds, so partially visible shapes draw their visible part.
+
+use alloc::vec;
+
+use crate::{AsciiSprite, TerminalChar};
+
+impl AsciiSprite {
+    /// Set a cell if `(x, y)` lies inside the sprite.
+    fn plot(&mut self, x: i64, y: i64, c: TerminalChar) {
+        if let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) {
+            self.set_char(x, y, c);
+        }
+    }
+
+    /// Draw a line from `(x0, y0)` to `(x1, y1)`, both ends included,
+    /// using Bresenham's algorithm.
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, c: TerminalChar) {
+        // i64 so the error terms can't overflow for any pair of i32 points
+        let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let (mut x, mut y) = (x0, y0);
+        let mut err = dx + dy;
+        loop {
+            self.plot(x, y, c);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draw the outline of the `w`x`h` rectangle with its top left corner at `(x, y)`.
+    pub fn draw_rect(&mut self, x: i32, y: i32, w: u16, h: u16, c: TerminalChar) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        let x1 = x.saturating_add(w as i32 - 1);
+        let y1 = y.saturating_add(h as i32 - 1);
+        self.draw_line(x, y, x1, y, c);
+        self.draw_line(x, y1, x1, y1, c);
+        self.draw_line(x, y, x, y1, c);
+        self.draw_line(x1, y, x1, y1, c);
+    }
+
+    /// Fill the `w`x`h` rectangle with its top left corner at `(x, y)`.
+    pub fn draw_filled_rect(&mut self, x: i32, y: i32, w: u16, h: u16, c: TerminalChar) {
+        let x0 = x.clamp(0, self.width as i32);
+        let x1 = x.saturating_add(w as i32).clamp(0, self.width as i32);
+        let y0 = y.clamp(0, self.height as i32);
+        let y1 = y.saturating_add(h as i32).clamp(0, self.height as i32);
+        for row in y0..y1 {
+            let start = row as usize * self.width as usize;
+            self.pixels[start + x0 as usize..start + x1 as usize].fill(c);
+        }
+    }
+
+    /// Replace the 4-connected region of cells equal to the one at `(x, y)`
+    /// (character and colours) with `replacement`, like a paint bucket.
+    ///
+    /// Does nothing if `(x, y)` is out of bounds.
+    pub fn flood_fill(&mut self, x: u16, y: u16, replacement: TerminalChar) {
+        let Some(target) = self.get_char(x, y) else {
+            return;
+        };
+        if target == replacement {
+            return;
+        }
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut stack = vec![(x as usize, y as usize)];
+        while let Some((x, y)) = stack.pop() {
+            let idx = y * w + x;
+            if self.pixels[idx] != target {
+                continue;
+            }
+            self.pixels[idx] = replacement;
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < w {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y + 1 < h {
+                stack.push((x, y + 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, TerminalString};
+
+    #[test]
+    fn test_draw_shapes_clipped() {
+        let blank = vec![TerminalChar::from('.'); 5 * 4];
+        let mut sprite = AsciiSprite::new(5, 4, blank).unwrap();
+        sprite.draw_line(-2, -2, 3, 3, TerminalChar::from('\\'));
+        assert_eq!(
+            sprite.to_plain_string(),
+            ["\\....", ".\\...", "..\\..", "...\\."].join("\n")
+        );
+
+        sprite.fill(TerminalChar::from('.'));
+        sprite.draw_rect(1, -1, 10, 3, TerminalChar::from('#'));
+        assert_eq!(
+            sprite.to_plain_string(),
+            [".#...", ".####", ".....", "....."].join("\n")
+        );
+
+        sprite.draw_filled_rect(-1, 2, 3, 9, TerminalChar::from('@'));
+        sprite.draw_filled_rect(7, 0, 2, 2, TerminalChar::from('!'));
+        assert_eq!(
+            sprite.to_plain_string(),
+            [".#...", ".####", "@@...", "@@..."].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let art = ["#####", "#..#.", "#.##.", "#...#"];
+        let mut sprite =
+            AsciiSprite::from_rows(art.into_iter().map(TerminalString::from).collect()).unwrap();
+
+        sprite.flood_fill(1, 1, TerminalChar::with_fg('~', Color::Blue));
+        assert_eq!(
+            sprite.to_plain_string(),
+            ["#####", "#~~#.", "#~##.", "#~~~#"].join("\n")
+        );
+        assert_eq!(sprite.get_char(2, 1).unwrap().fg_color, Some(Color::Blue));
+
+        // the coloured fill no longer matches a plain '.'
+        sprite.flood_fill(4, 1, TerminalChar::from('~'));
+        assert_eq!(
+            sprite.to_plain_string(),
+            ["#####", "#~~#~", "#~##~", "#~~~#"].join("\n")
+        );
+
+        let before = sprite.clone();
+        sprite.flood_fill(5, 0, TerminalChar::from('x'));
+        assert_eq!(sprite, before);
+    }
+}
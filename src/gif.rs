@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use byteorder::LittleEndian;
+
+use crate::{AsciiVideo, Color, TerminalChar, io::WriteBytesExt, raster::RgbaImage};
+
+impl AsciiVideo {
+    /// Export the video as an endlessly looping animated GIF.
+    ///
+    /// Each frame is rendered with [`crate::AsciiSprite::render_rgba`], so
+    /// `glyph` draws a single character into its `cell_w × cell_h` block
+    /// (see [`crate::raster::block_glyph`] for a font-less fallback).
+    /// Pixels are quantized to the ANSI-256 palette, and every frame is shown
+    /// for its [`AsciiVideo::frame_duration`], rounded to GIF's 10ms
+    /// resolution.
+    ///
+    /// ## Error
+    /// if a cell dimension is 0, the rendered image exceeds 65535 pixels in
+    /// either direction, the video has no timing, or writing fails
+    pub fn export_gif<F>(
+        &self,
+        path: impl AsRef<Path>,
+        cell_w: u32,
+        cell_h: u32,
+        glyph: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(&TerminalChar, &mut RgbaImage, u32, u32),
+    {
+        let f = File::create(path)?;
+        let mut w = BufWriter::new(f);
+        self.write_gif(&mut w, cell_w, cell_h, glyph)?;
+        w.flush()
+    }
+
+    fn write_gif<W, F>(&self, w: &mut W, cell_w: u32, cell_h: u32, glyph: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&TerminalChar, &mut RgbaImage, u32, u32),
+    {
+        // in hundredths of a second
+        let delays = (0..self.frames.len())
+            .map(|i| self.frame_duration(i))
+            .map(|d| {
+                Some(
+                    (d?.as_secs_f64() * 100.0)
+                        .round()
+                        .clamp(1.0, u16::MAX as f64) as u16,
+                )
+            })
+            .collect::<Option<Vec<u16>>>()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "video needs a frame rate or frame durations to export a GIF",
+                )
+            })?;
+        let px_w = (self.width as u32).checked_mul(cell_w).map(u16::try_from);
+        let px_h = (self.height as u32).checked_mul(cell_h).map(u16::try_from);
+        let (Some(Ok(px_w)), Some(Ok(px_h))) = (px_w, px_h) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "rendered image too large for GIF, max 65535x65535",
+            ));
+        };
+
+        // Header, logical screen with a 256 entry global colour table
+        w.write_all(b"GIF89a")?;
+        w.write_u16::<LittleEndian>(px_w)?;
+        w.write_u16::<LittleEndian>(px_h)?;
+        w.write_all(&[0xF7, 0, 0])?;
+        for code in 0..=255 {
+            let (r, g, b) = Color::ansi256_to_rgb(code);
+            w.write_all(&[r, g, b])?;
+        }
+
+        // Loop forever
+        w.write_all(&[0x21, 0xFF, 0x0B])?;
+        w.write_all(b"NETSCAPE2.0")?;
+        w.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        let mut palette = HashMap::new();
+        for (frame, delay) in self.frames.iter().zip(delays) {
+            let img = frame.render_rgba(cell_w, cell_h, &glyph)?;
+            let indices: Vec<u8> = img
+                .as_raw()
+                .chunks_exact(4)
+                .map(|px| {
+                    *palette
+                        .entry([px[0], px[1], px[2]])
+                        .or_insert_with(|| Color::rgb_to_ansi256(px[0], px[1], px[2]))
+                })
+                .collect();
+
+            // Graphic control extension
+            w.write_all(&[0x21, 0xF9, 0x04, 0x04])?;
+            w.write_u16::<LittleEndian>(delay)?;
+            w.write_all(&[0, 0])?;
+
+            // Image descriptor, full frame and no local colour table
+            w.write_u8(0x2C)?;
+            w.write_u16::<LittleEndian>(0)?;
+            w.write_u16::<LittleEndian>(0)?;
+            w.write_u16::<LittleEndian>(px_w)?;
+            w.write_u16::<LittleEndian>(px_h)?;
+            w.write_u8(0)?;
+
+            w.write_u8(MIN_CODE_SIZE)?;
+            for block in lzw_encode(&indices).chunks(255) {
+                w.write_u8(block.len() as u8)?;
+                w.write_all(block)?;
+            }
+            w.write_u8(0)?;
+        }
+
+        w.write_u8(0x3B)
+    }
+}
+
+const MIN_CODE_SIZE: u8 = 8;
+const MAX_CODE: u16 = 4096;
+
+/// GIF flavoured LZW compression of palette indices.
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let eoi = clear + 1;
+
+    let mut out = BitWriter::default();
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = eoi + 1;
+    let mut code_size = MIN_CODE_SIZE + 1;
+
+    out.write(clear, code_size);
+    let mut iter = indices.iter();
+    if let Some(&first) = iter.next() {
+        let mut prefix = first as u16;
+        for &k in iter {
+            if let Some(&code) = dict.get(&(prefix, k)) {
+                prefix = code;
+                continue;
+            }
+            out.write(prefix, code_size);
+            if next < MAX_CODE {
+                dict.insert((prefix, k), next);
+                next += 1;
+                if next > (1 << code_size) && code_size < 12 {
+                    code_size += 1;
+                }
+            } else {
+                out.write(clear, code_size);
+                dict.clear();
+                next = eoi + 1;
+                code_size = MIN_CODE_SIZE + 1;
+            }
+            prefix = k as u16;
+        }
+        out.write(prefix, code_size);
+    }
+    out.write(eoi, code_size);
+    out.finish()
+}
+
+/// Packs codes least significant bit first, as GIF expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsciiSprite, raster::block_glyph};
+    use std::time::Duration;
+
+    /// Reference GIF LZW decoder.
+    fn lzw_decode(data: &[u8]) -> Vec<u8> {
+        let clear = 1u16 << MIN_CODE_SIZE;
+        let eoi = clear + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            table.clear();
+            table.extend((0..=255u8).map(|b| vec![b]));
+            table.push(vec![]);
+            table.push(vec![]);
+        };
+        reset(&mut table);
+
+        let mut out = Vec::new();
+        let mut code_size = MIN_CODE_SIZE + 1;
+        let mut prev: Option<Vec<u8>> = None;
+        let (mut acc, mut bits, mut pos) = (0u32, 0u8, 0usize);
+        loop {
+            while bits < code_size {
+                acc |= (data[pos] as u32) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (acc & ((1 << code_size) - 1)) as u16;
+            acc >>= code_size;
+            bits -= code_size;
+
+            if code == clear {
+                reset(&mut table);
+                code_size = MIN_CODE_SIZE + 1;
+                prev = None;
+                continue;
+            }
+            if code == eoi {
+                return out;
+            }
+            let entry = match (table.get(code as usize), &prev) {
+                (Some(e), _) => e.clone(),
+                (None, Some(p)) => {
+                    let mut e = p.clone();
+                    e.push(p[0]);
+                    e
+                }
+                (None, None) => panic!("invalid code"),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(p) = prev {
+                if table.len() < MAX_CODE as usize {
+                    let mut e = p;
+                    e.push(entry[0]);
+                    table.push(e);
+                }
+                if table.len() == (1 << code_size) && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw_roundtrip() {
+        let mut data: Vec<u8> = (0..20_000u32).map(|i| (i * i % 251) as u8).collect();
+        data.extend(std::iter::repeat_n(7, 5000));
+        assert_eq!(lzw_decode(&lzw_encode(&data)), data);
+        assert_eq!(lzw_decode(&lzw_encode(&[3])), vec![3]);
+    }
+
+    #[test]
+    fn test_gif_structure() {
+        let frame =
+            AsciiSprite::new(2, 1, vec![TerminalChar::with_fg('#', Color::Red); 2]).unwrap();
+        let mut video = AsciiVideo::new(2, 1, vec![frame.clone(), frame]).unwrap();
+        assert!(
+            video
+                .write_gif(&mut Vec::new(), 4, 6, block_glyph(4, 6))
+                .is_err()
+        );
+        video.frame_rate = Some(20.0);
+
+        let mut buf = Vec::new();
+        video.write_gif(&mut buf, 4, 6, block_glyph(4, 6)).unwrap();
+        assert_eq!(&buf[..6], b"GIF89a");
+        assert_eq!(&buf[6..10], &[8, 0, 6, 0]);
+        assert_eq!(buf.last(), Some(&0x3B));
+        // two graphic control extensions with a 5/100s delay
+        let gce = [0x21, 0xF9, 0x04, 0x04, 5, 0];
+        assert_eq!(buf.windows(6).filter(|w| *w == gce).count(), 2);
+
+        // per-frame durations take precedence
+        video.frame_durations = vec![Duration::from_millis(120), Duration::from_secs(2)];
+        let mut buf = Vec::new();
+        video.write_gif(&mut buf, 4, 6, block_glyph(4, 6)).unwrap();
+        for delay in [12, 200] {
+            let gce = [0x21, 0xF9, 0x04, 0x04, delay, 0];
+            assert_eq!(buf.windows(6).filter(|w| *w == gce).count(), 1);
+        }
+
+        let err = video
+            .write_gif(&mut Vec::new(), u32::MAX, 6, block_glyph(4, 6))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,469 @@
+//! DEFLATE decompression and gzip (RFC 1952) streams, behind the `gzip`
+//! feature.
+//!
+//! The decompressor handles all block types, so files from other gzip tools
+//! can be read. Compression is shared with PNG export in `deflate`.
+
+use std::io;
+
+use crate::deflate::{DIST_BASE, DIST_EXTRA, GZIP_MAGIC, LEN_BASE, LEN_EXTRA, compress, crc32};
+
+/// Order in which code length code lengths are stored in a dynamic block.
+const CLEN_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn corrupt(msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("corrupt deflate stream: {}", msg),
+    )
+}
+
+/// Reads bits least significant first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    acc: u32,
+    bits: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    fn bits(&mut self, n: u32) -> io::Result<u32> {
+        while self.bits < n {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or(io::ErrorKind::UnexpectedEof)?;
+            self.pos += 1;
+            self.acc |= (byte as u32) << self.bits;
+            self.bits += 8;
+        }
+        let v = self.acc & ((1u64 << n) - 1) as u32;
+        self.acc >>= n;
+        self.bits -= n;
+        Ok(v)
+    }
+
+    fn align(&mut self) {
+        self.acc = 0;
+        self.bits = 0;
+    }
+}
+
+/// Canonical Huffman decoding table.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Self> {
+        let mut counts = [0u16; 16];
+        for &l in lengths {
+            counts[l as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &c in &counts[1..] {
+            left = (left << 1) - c as i32;
+            if left < 0 {
+                return Err(corrupt("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offs = [0u16; 16];
+        for len in 1..15 {
+            offs[len + 1] = offs[len] + counts[len];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (sym, &l) in lengths.iter().enumerate() {
+            if l != 0 {
+                symbols[offs[l as usize] as usize] = sym as u16;
+                offs[l as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, r: &mut BitReader) -> io::Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= r.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(corrupt("invalid Huffman code"))
+    }
+}
+
+/// Decompress a raw DEFLATE stream, stopping once `limit` bytes are out.
+///
+/// ## return
+/// the decompressed bytes, at most `limit`, and the number of input bytes
+/// consumed, or `None` if decompression stopped at the limit
+pub(crate) fn decompress(data: &[u8], limit: usize) -> io::Result<(Vec<u8>, Option<usize>)> {
+    let mut r = BitReader::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let last = r.bits(1)? == 1;
+        match r.bits(2)? {
+            0 => {
+                r.align();
+                let header = data
+                    .get(r.pos..r.pos + 4)
+                    .ok_or(io::ErrorKind::UnexpectedEof)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+                if len != !nlen {
+                    return Err(corrupt("stored block length mismatch"));
+                }
+                r.pos += 4;
+                let block = data
+                    .get(r.pos..r.pos + len as usize)
+                    .ok_or(io::ErrorKind::UnexpectedEof)?;
+                out.extend_from_slice(block);
+                r.pos += len as usize;
+                if out.len() >= limit {
+                    break;
+                }
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let lit = Huffman::new(&lengths)?;
+                let dist = Huffman::new(&[5; 30])?;
+                if !inflate_block(&mut r, &mut out, &lit, &dist, limit)? {
+                    break;
+                }
+            }
+            2 => {
+                let (lit, dist) = read_dynamic_tables(&mut r)?;
+                if !inflate_block(&mut r, &mut out, &lit, &dist, limit)? {
+                    break;
+                }
+            }
+            _ => return Err(corrupt("invalid block type")),
+        }
+        if last {
+            return Ok((out, Some(r.pos)));
+        }
+    }
+
+    out.truncate(limit);
+    Ok((out, None))
+}
+
+fn read_dynamic_tables(r: &mut BitReader) -> io::Result<(Huffman, Huffman)> {
+    let hlit = r.bits(5)? as usize + 257;
+    let hdist = r.bits(5)? as usize + 1;
+    let hclen = r.bits(4)? as usize + 4;
+    if hlit > 286 || hdist > 30 {
+        return Err(corrupt("too many length or distance codes"));
+    }
+
+    let mut clen = [0u8; 19];
+    for &i in &CLEN_ORDER[..hclen] {
+        clen[i] = r.bits(3)? as u8;
+    }
+    let clen = Huffman::new(&clen)?;
+
+    let mut lengths = vec![0u8; hlit + hdist];
+    let mut i = 0;
+    while i < lengths.len() {
+        let sym = clen.decode(r)?;
+        let (value, repeat) = match sym {
+            0..=15 => (sym as u8, 1),
+            16 => {
+                let prev = *lengths[..i]
+                    .last()
+                    .ok_or_else(|| corrupt("repeat without previous length"))?;
+                (prev, 3 + r.bits(2)? as usize)
+            }
+            17 => (0, 3 + r.bits(3)? as usize),
+            _ => (0, 11 + r.bits(7)? as usize),
+        };
+        if i + repeat > lengths.len() {
+            return Err(corrupt("too many code lengths"));
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(corrupt("missing end-of-block code"));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..hlit])?,
+        Huffman::new(&lengths[hlit..])?,
+    ))
+}
+
+/// Decode one Huffman coded block into `out`.
+///
+/// ## return
+/// `true` at the end of the block, `false` if `out` reached `limit` first
+fn inflate_block(
+    r: &mut BitReader,
+    out: &mut Vec<u8>,
+    lit: &Huffman,
+    dist: &Huffman,
+    limit: usize,
+) -> io::Result<bool> {
+    while out.len() < limit {
+        let sym = lit.decode(r)? as usize;
+        match sym {
+            0..=255 => out.push(sym as u8),
+            256 => return Ok(true),
+            257..=285 => {
+                let li = sym - 257;
+                let len = LEN_BASE[li] as usize + r.bits(LEN_EXTRA[li] as u32)? as usize;
+                let di = dist.decode(r)? as usize;
+                if di >= 30 {
+                    return Err(corrupt("invalid distance code"));
+                }
+                let d = DIST_BASE[di] as usize + r.bits(DIST_EXTRA[di] as u32)? as usize;
+                if d > out.len() {
+                    return Err(corrupt("distance too far back"));
+                }
+                let start = out.len() - d;
+                for k in 0..len {
+                    out.push(out[start + k]);
+                }
+            }
+            _ => return Err(corrupt("invalid literal/length code")),
+        }
+    }
+    Ok(false)
+}
+
+/// Wrap `data` into a gzip stream.
+pub(crate) fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 4 + 18);
+    out.extend_from_slice(&GZIP_MAGIC);
+    // deflate, no flags, no mtime, no extra flags, unknown OS
+    out.extend_from_slice(&[8, 0, 0, 0, 0, 0, 0, 255]);
+    out.extend_from_slice(&compress(data));
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Decode the first member of a gzip stream, verifying its checksum.
+///
+/// ## Error
+/// `InvalidData` if the stream is corrupt or decompresses to more than
+/// `limit` bytes, which is detected without inflating past the limit
+pub(crate) fn gunzip(data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+    let body = gzip_body(data)?;
+    let (out, used) = decompress(body, limit.saturating_add(1))?;
+    let used = used
+        .filter(|_| out.len() <= limit)
+        .ok_or_else(|| bad_gzip(&format!("decompressed data exceeds {} bytes", limit)))?;
+    let trailer = body
+        .get(used..used + 8)
+        .ok_or(io::ErrorKind::UnexpectedEof)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&out) || size != out.len() as u32 {
+        return Err(bad_gzip("checksum mismatch"));
+    }
+    Ok(out)
+}
+
+/// The first `len` decompressed bytes of a gzip stream, or all of them if
+/// there are fewer. Only the part of the stream producing them is read, so
+/// the checksum isn't verified.
+pub(crate) fn gunzip_prefix(data: &[u8], len: usize) -> io::Result<Vec<u8>> {
+    decompress(gzip_body(data)?, len).map(|(out, _)| out)
+}
+
+/// The DEFLATE stream of a gzip member, after its header.
+fn gzip_body(data: &[u8]) -> io::Result<&[u8]> {
+    if data.len() < 18 || data[..2] != GZIP_MAGIC {
+        return Err(bad_gzip("not a gzip stream"));
+    }
+    if data[2] != 8 {
+        return Err(bad_gzip("unsupported compression method"));
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & 0x04 != 0 {
+        let xlen = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+        pos += 2 + xlen;
+    }
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            let end = data
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(|| bad_gzip("unterminated header string"))?;
+            pos += end + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        pos += 2;
+    }
+    data.get(pos..)
+        .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+}
+
+fn bad_gzip(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("gzip: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deflate::{adler32, zlib};
+
+    #[test]
+    fn test_zlib() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 97) as u8).collect();
+        let z = zlib(&data);
+        assert_eq!(u16::from_be_bytes([z[0], z[1]]) % 31, 0);
+        let (out, used) = decompress(&z[2..], usize::MAX).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(z[2 + used.unwrap()..], adler32(&data).to_be_bytes());
+    }
+
+    #[test]
+    fn test_deflate_roundtrip() {
+        let mut data: Vec<u8> = (0..50_000u32).map(|i| (i * 7 % 13) as u8).collect();
+        data.extend((0..70_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8));
+        data.extend(std::iter::repeat_n(b' ', 100_000));
+        for input in [&data[..], b"", b"a", b"abcabcabcabc"] {
+            let (out, used) = decompress(&compress(input), usize::MAX).unwrap();
+            assert_eq!(out, input);
+            assert_eq!(used, Some(compress(input).len()));
+        }
+        let (prefix, used) = decompress(&compress(&data), 1000).unwrap();
+        assert_eq!((&prefix[..], used), (&data[..1000], None));
+        assert!(compress(&data).len() < data.len() / 2);
+    }
+
+    #[test]
+    fn test_inflate_stored_and_dynamic() {
+        // stored block "hi"
+        let stored = [0x01, 0x02, 0x00, 0xFD, 0xFF, b'h', b'i'];
+        assert_eq!(decompress(&stored, usize::MAX).unwrap().0, b"hi");
+
+        // raw deflate output of zlib level 9, fixed and dynamic Huffman blocks
+        let fixed = [0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x27, 0x01];
+        assert_eq!(
+            decompress(&fixed, usize::MAX).unwrap().0,
+            b"hello hello hello hello"
+        );
+
+        let dynamic = [
+            0xED, 0xCA, 0xCB, 0x15, 0x40, 0x30, 0x10, 0x00, 0xC0, 0x56, 0xB6, 0x02, 0xCF, 0xFF,
+            0xD3, 0x87, 0x06, 0x12, 0x82, 0x04, 0x59, 0x89, 0x44, 0x50, 0x3D, 0x57, 0x1D, 0x38,
+            0xEC, 0x79, 0xA6, 0x9D, 0x04, 0x18, 0x2F, 0xBB, 0x19, 0xB8, 0xC5, 0xA0, 0x61, 0xC0,
+            0x13, 0x94, 0x5F, 0xB7, 0x1D, 0xF0, 0x10, 0x16, 0xDC, 0xCB, 0x0B, 0xBB, 0x2F, 0xE8,
+            0x71, 0x8C, 0xA0, 0xA5, 0x4C, 0x99, 0x32, 0x65, 0xCA, 0xDF, 0xCC, 0x78, 0xD7, 0x8B,
+            0x61, 0x9C, 0xA4, 0x9A, 0x97, 0x55, 0xE3, 0x66, 0xEC, 0xEE, 0xFC, 0x11, 0xCE, 0xEB,
+            0x8E, 0x93, 0x34, 0xCB, 0x8B, 0xB2, 0xAA, 0x9B, 0xBF, 0x9D, 0x07,
+        ];
+        let mut expected = b"The quick brown fox jumps over the lazy dog. ".repeat(30);
+        expected.extend(b"abcdefghijklmnopqrstuvwxyz0123456789".repeat(5));
+        assert_eq!(
+            decompress(&dynamic, usize::MAX).unwrap(),
+            (expected.clone(), Some(dynamic.len()))
+        );
+        assert_eq!(decompress(&stored, 1).unwrap(), (b"h".to_vec(), None));
+        assert_eq!(decompress(&dynamic, 50).unwrap().0, expected[..50]);
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let data = b"ASCV ascii video payload payload payload".repeat(50);
+        let gz = gzip(&data);
+        assert_eq!(gz[..2], GZIP_MAGIC);
+        assert_eq!(gunzip(&gz, data.len()).unwrap(), data);
+        assert_eq!(gunzip_prefix(&gz, 4).unwrap(), b"ASCV");
+        let err = gunzip(&gz, data.len() - 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut broken = gz.clone();
+        let n = broken.len();
+        broken[n - 5] ^= 1;
+        assert!(gunzip(&broken, usize::MAX).is_err());
+    }
+
+    /// Eight 30 byte lines, as in a tiny text video.
+    fn lines() -> Vec<u8> {
+        let mut data = b"frame 1: ####################\n".repeat(4);
+        data.extend(b"frame 2: ....................\n".repeat(4));
+        data
+    }
+
+    /// Sixty words with varying separators, enough for a dynamic block.
+    fn words() -> Vec<u8> {
+        let words = "sprite video frame colour cell glyph palette terminal reset index header";
+        let words: Vec<_> = words.split(' ').collect();
+        (0..60)
+            .flat_map(|i| {
+                let sep = if i % 5 == 0 { "!" } else { " " };
+                [words[i * 7 % words.len()], sep]
+            })
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_gnu_gzip_vectors() {
+        // `gzip -9 lines.txt` (GNU gzip 1.12): a header with file name and
+        // mtime, then a fixed Huffman block
+        let fixed: [u8; 55] = [
+            0x1F, 0x8B, 0x08, 0x08, 0x80, 0x00, 0x92, 0x65, 0x02, 0x03, 0x66, 0x72, 0x61, 0x6D,
+            0x65, 0x73, 0x2E, 0x74, 0x78, 0x74, 0x00, 0x4B, 0x2B, 0x4A, 0xCC, 0x4D, 0x55, 0x30,
+            0xB4, 0x52, 0x50, 0xC6, 0x02, 0xB8, 0xD2, 0x68, 0x2C, 0x6B, 0x64, 0xA5, 0xA0, 0x87,
+            0x05, 0xD0, 0x50, 0x16, 0x00, 0x3F, 0xF1, 0x39, 0x0A, 0xF0, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(gunzip(&fixed, usize::MAX).unwrap(), lines());
+
+        // `gzip -9 -n` of `words()`: a dynamic Huffman block
+        let dynamic: [u8; 130] = [
+            0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x85, 0xD0, 0x8B, 0x0D,
+            0xC2, 0x30, 0x14, 0x43, 0xD1, 0x55, 0xEC, 0xD1, 0xA2, 0xC6, 0xD0, 0x48, 0xAF, 0x4D,
+            0xF4, 0x12, 0x10, 0x6C, 0x8F, 0x28, 0x48, 0x40, 0x3F, 0x61, 0x81, 0x23, 0xFB, 0xD6,
+            0xE2, 0xA9, 0x89, 0x4D, 0x3E, 0xA5, 0x39, 0x18, 0x86, 0x6C, 0xF9, 0xE2, 0x18, 0x15,
+            0xA2, 0x1C, 0x25, 0x98, 0x5A, 0x13, 0x4E, 0x1E, 0x26, 0x31, 0xCD, 0x51, 0x37, 0x9C,
+            0xED, 0x5E, 0x46, 0x5C, 0x53, 0x54, 0x86, 0xAB, 0xAA, 0x61, 0x90, 0x19, 0xEB, 0x02,
+            0xA1, 0x0F, 0x71, 0x81, 0x70, 0x00, 0xF1, 0x09, 0xA1, 0x0B, 0xF1, 0x67, 0xD1, 0x16,
+            0xE2, 0x67, 0xD1, 0x01, 0xC4, 0xBD, 0x6B, 0xDF, 0x10, 0xD7, 0xD7, 0xD6, 0x10, 0x3B,
+            0x8D, 0x5E, 0x10, 0xF7, 0x1B, 0xBD, 0xA1, 0x7F, 0xB1, 0x1F, 0xA9, 0xDD, 0x3E, 0xEF,
+            0x93, 0x01, 0x00, 0x00,
+        ];
+        assert_eq!(gunzip(&dynamic, usize::MAX).unwrap(), words());
+
+        // the other way round, `gzip -t` accepts this and `gzip -d` restores
+        // `lines()` from it
+        let ours: [u8; 43] = [
+            0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x4B, 0x2B, 0x4A, 0xCC,
+            0x4D, 0x55, 0x30, 0xB4, 0x52, 0x50, 0xC6, 0x02, 0xB8, 0x68, 0x2D, 0x6B, 0x64, 0xA5,
+            0xA0, 0x87, 0x05, 0xD0, 0x50, 0x16, 0x00, 0x3F, 0xF1, 0x39, 0x0A, 0xF0, 0x00, 0x00,
+            0x00,
+        ];
+        assert_eq!(gzip(&lines()), ours);
+    }
+}
@@ -0,0 +1,256 @@
+//! Extension block of the video header (format version 2 and up).
+//!
+//! The block is a sequence of sections:
+//! ```text
+//! u8               tag
+//! u32 + bytes      section payload
+//! ```
+//! Readers skip sections with a tag they don't know, unless the tag has the
+//! [`CRITICAL`] bit set, which marks data the frames can't be decoded without.
+
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::time::Duration;
+
+use byteorder::LittleEndian;
+
+use crate::{
+    CharEncoding, frame_period,
+    io::{self, Read, ReadBytesExt, Write, WriteBytesExt},
+};
+
+/// Set on tags of sections a reader must understand to decode the file.
+pub(crate) const CRITICAL: u8 = 0x80;
+
+/// `u16` pair count, then every key and value as `u32` length + UTF-8 bytes.
+pub(crate) const TAG_METADATA: u8 = 1;
+
+/// `u8` code point encoding of the frames: 1 for [`CharEncoding::Varint`].
+/// Absent for [`CharEncoding::Fixed`], so those files stay readable by 2.1.
+pub(crate) const TAG_CHAR_ENCODING: u8 = CRITICAL | 2;
+
+/// `f32` frame rate (0 if unset), `u8` flags (bit 0: looping), then a `u32`
+/// count of per-frame durations (0 or the frame count), each a `u32` in ms.
+pub(crate) const TAG_TIMING: u8 = 3;
+
+/// `u32` count of frame labels (0 or the frame count), each a `u8` flag,
+/// 1 followed by a `u32` length + UTF-8 bytes if the frame has a label.
+pub(crate) const TAG_LABELS: u8 = 4;
+
+/// No payload. Marks files whose cells may store colours as ANSI-256
+/// palette indices, which older readers would misread.
+pub(crate) const TAG_INDEXED_COLORS: u8 = CRITICAL | 5;
+
+/// Everything stored in the header extension.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Extensions {
+    pub metadata: BTreeMap<String, String>,
+    pub char_encoding: CharEncoding,
+    pub frame_rate: Option<f32>,
+    pub looping: bool,
+    pub frame_durations: Vec<Duration>,
+    pub labels: Vec<Option<String>>,
+    pub indexed_colors: bool,
+}
+
+/// The same settings as [`Extensions`], borrowed from a video to write them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExtensionsRef<'a> {
+    pub metadata: &'a BTreeMap<String, String>,
+    pub char_encoding: CharEncoding,
+    pub frame_rate: Option<f32>,
+    pub looping: bool,
+    pub frame_durations: &'a [Duration],
+    pub labels: &'a [Option<String>],
+    pub indexed_colors: bool,
+}
+
+impl ExtensionsRef<'_> {
+    /// Serialise all non-empty sections.
+    pub(crate) fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+
+        if !self.metadata.is_empty() {
+            let count = u16::try_from(self.metadata.len()).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "too many metadata entries: {} (max {})",
+                        self.metadata.len(),
+                        u16::MAX
+                    ),
+                )
+            })?;
+            let mut section = Vec::new();
+            section.write_u16::<LittleEndian>(count)?;
+            for (key, value) in self.metadata {
+                write_str(&mut section, key)?;
+                write_str(&mut section, value)?;
+            }
+            write_section(&mut out, TAG_METADATA, &section)?;
+        }
+
+        if self.frame_rate.is_some() || self.looping || !self.frame_durations.is_empty() {
+            let mut section = Vec::new();
+            section.write_f32::<LittleEndian>(self.frame_rate.unwrap_or(0.0))?;
+            section.write_u8(self.looping as u8)?;
+            section.write_u32::<LittleEndian>(self.frame_durations.len() as u32)?;
+            for d in self.frame_durations {
+                let ms = u32::try_from(d.as_millis()).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("frame duration {:?} too long", d),
+                    )
+                })?;
+                section.write_u32::<LittleEndian>(ms)?;
+            }
+            write_section(&mut out, TAG_TIMING, &section)?;
+        }
+
+        if !self.labels.is_empty() {
+            let mut section = Vec::new();
+            section.write_u32::<LittleEndian>(self.labels.len() as u32)?;
+            for label in self.labels {
+                match label {
+                    Some(label) => {
+                        section.write_u8(1)?;
+                        write_str(&mut section, label)?;
+                    }
+                    None => section.write_u8(0)?,
+                }
+            }
+            write_section(&mut out, TAG_LABELS, &section)?;
+        }
+
+        if self.char_encoding == CharEncoding::Varint {
+            write_section(&mut out, TAG_CHAR_ENCODING, &[1])?;
+        }
+
+        if self.indexed_colors {
+            write_section(&mut out, TAG_INDEXED_COLORS, &[])?;
+        }
+
+        Ok(out)
+    }
+}
+
+impl Extensions {
+    /// Parse an extension block, skipping unknown optional sections.
+    pub(crate) fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        let mut ext = Self::default();
+        while !bytes.is_empty() {
+            let tag = bytes.read_u8()?;
+            let len = bytes.read_u32::<LittleEndian>()? as usize;
+            if len > bytes.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("header section {} overruns the header extension", tag),
+                ));
+            }
+            let (mut section, rest) = bytes.split_at(len);
+            bytes = rest;
+
+            match tag {
+                TAG_METADATA => {
+                    let count = section.read_u16::<LittleEndian>()?;
+                    for _ in 0..count {
+                        let key = read_str(&mut section)?;
+                        let value = read_str(&mut section)?;
+                        ext.metadata.insert(key, value);
+                    }
+                }
+                TAG_CHAR_ENCODING => {
+                    ext.char_encoding = match section.read_u8()? {
+                        0 => CharEncoding::Fixed,
+                        1 => CharEncoding::Varint,
+                        e => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("unknown character encoding {}", e),
+                            ));
+                        }
+                    };
+                }
+                TAG_TIMING => {
+                    let rate = section.read_f32::<LittleEndian>()?;
+                    ext.frame_rate = (rate > 0.0 && rate.is_finite()).then_some(rate);
+                    if frame_period(rate).is_none() && ext.frame_rate.is_some() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("frame rate {} is too low", rate),
+                        ));
+                    }
+                    ext.looping = section.read_u8()? & 1 != 0;
+                    let count = section.read_u32::<LittleEndian>()? as usize;
+                    if count > section.len() / 4 {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                    ext.frame_durations = (0..count)
+                        .map(|_| {
+                            let ms = section.read_u32::<LittleEndian>()?;
+                            Ok(Duration::from_millis(ms as u64))
+                        })
+                        .collect::<io::Result<_>>()?;
+                }
+                TAG_LABELS => {
+                    let count = section.read_u32::<LittleEndian>()? as usize;
+                    if count > section.len() {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                    ext.labels = (0..count)
+                        .map(|_| match section.read_u8()? {
+                            0 => Ok(None),
+                            _ => read_str(&mut section).map(Some),
+                        })
+                        .collect::<io::Result<_>>()?;
+                }
+                TAG_INDEXED_COLORS => ext.indexed_colors = true,
+                t if t & CRITICAL != 0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unsupported required header section {}", t),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(ext)
+    }
+}
+
+fn write_section<W: Write>(w: &mut W, tag: u8, payload: &[u8]) -> io::Result<()> {
+    w.write_u8(tag)?;
+    w.write_u32::<LittleEndian>(payload.len() as u32)?;
+    w.write_all(payload)
+}
+
+fn write_str<W: Write>(w: &mut W, s: &str) -> io::Result<()> {
+    w.write_u32::<LittleEndian>(s.len() as u32)?;
+    w.write_all(s.as_bytes())
+}
+
+fn read_str(bytes: &mut &[u8]) -> io::Result<String> {
+    let len = bytes.read_u32::<LittleEndian>()? as usize;
+    if len > bytes.len() {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let mut buf = vec![0; len];
+    bytes.read_exact(&mut buf)?;
+    String::from_utf8(buf)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "header string is not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_sections() {
+        let mut block = Vec::new();
+        write_section(&mut block, 0x7F, &[1, 2, 3]).unwrap();
+        assert_eq!(Extensions::decode(&block).unwrap(), Extensions::default());
+
+        write_section(&mut block, 0x7F | CRITICAL, &[]).unwrap();
+        let err = Extensions::decode(&block).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,390 @@
+use std::io;
+
+use crate::{AsciiSprite, Color, Ramp, TerminalChar, density::coverage, raster::RgbaImage};
+
+/// Terminal cells are roughly twice as tall as they are wide.
+const CELL_ASPECT: f32 = 2.0;
+
+/// Brightness difference across a cell from which
+/// [`AsciiSprite::from_image_structural`] draws it as an edge.
+const EDGE_THRESHOLD: f32 = 0.25;
+
+impl AsciiSprite {
+    /// Convert an image to ASCII art.
+    ///
+    /// The image is split into a `cols × rows` grid, the average colour of
+    /// each block becomes the cell's foreground colour and its luminance
+    /// picks a character from `ramp`, e.g. [`Ramp::classic`].
+    /// Transparent pixels count as black.
+    ///
+    /// Pass `rows == 0` to derive the row count from the image's aspect
+    /// ratio, accounting for terminal cells being about twice as tall as wide.
+    ///
+    /// ## Error
+    /// if the image is empty or `cols` is 0
+    pub fn from_image(img: &RgbaImage, cols: u16, rows: u16, ramp: &Ramp) -> io::Result<Self> {
+        Self::convert_image(img, cols, rows, ramp, false)
+    }
+
+    /// Like [`AsciiSprite::from_image`], but with Floyd–Steinberg error
+    /// diffusion: the rounding error of every cell's luminance is passed on
+    /// to its unvisited neighbours, so gradients become a mix of adjacent
+    /// ramp characters instead of flat bands. No randomness is involved,
+    /// the same image always gives the same sprite.
+    ///
+    /// ## Error
+    /// see [`AsciiSprite::from_image`]
+    pub fn from_image_dithered(
+        img: &RgbaImage,
+        cols: u16,
+        rows: u16,
+        ramp: &Ramp,
+    ) -> io::Result<Self> {
+        Self::convert_image(img, cols, rows, ramp, true)
+    }
+
+    /// Convert an image using the upper half block `▀`, so every cell shows
+    /// two pixels on top of each other: the block's foreground is the upper
+    /// one, the background the lower one. This doubles the vertical
+    /// resolution compared to [`AsciiSprite::from_image`].
+    ///
+    /// The image is split into `cols` columns and `rows * 2` pixel rows.
+    /// With `rows == 0` the pixel rows are derived from the aspect ratio
+    /// instead, and if that gives an odd number the missing lower half of
+    /// the last row is black.
+    ///
+    /// ## Error
+    /// if the image is empty or `cols` is 0
+    pub fn from_image_halfblock(img: &RgbaImage, cols: u16, rows: u16) -> io::Result<Self> {
+        // validates the image and column count, rows are resolved below
+        grid_size(img, cols, 1)?;
+        let px_rows = if rows == 0 {
+            let px_rows = cols as f32 * img.height() as f32 / img.width() as f32;
+            px_rows.round().clamp(1.0, 2.0 * u16::MAX as f32) as u32
+        } else {
+            2 * rows as u32
+        };
+        let rows = px_rows.div_ceil(2) as u16;
+
+        let mut pixels = Vec::with_capacity(cols as usize * rows as usize);
+        for row in 0..rows as u32 {
+            for col in 0..cols as u32 {
+                let (r, g, b) = average_block(img, cols as u32, px_rows, col, 2 * row);
+                let lower = 2 * row + 1;
+                let bg = if lower < px_rows {
+                    let (r, g, b) = average_block(img, cols as u32, px_rows, col, lower);
+                    Color::rgb(r, g, b)
+                } else {
+                    Color::Black
+                };
+                pixels.push(TerminalChar::with_colors('▀', Color::rgb(r, g, b), bg));
+            }
+        }
+        Self::new(cols, rows, pixels)
+    }
+
+    /// Convert an image to ASCII art, paying attention to the shape of the
+    /// glyphs.
+    ///
+    /// Every cell gets the character of `ramp` whose ink coverage (from a
+    /// built-in table of typical glyph shapes, see
+    /// [`AsciiSprite::density_map`]) best matches the brightness of the
+    /// block, rather than just the one at the matching position in the
+    /// ramp. Blocks that are much brighter on one side than the other are
+    /// drawn as lines along the edge instead: `|`, `-`, `/` or `\`. This
+    /// keeps outlines crisp where [`AsciiSprite::from_image`] blurs them.
+    /// Colours and the grid are computed as in [`AsciiSprite::from_image`].
+    ///
+    /// ## Error
+    /// if the image is empty or `cols` is 0
+    pub fn from_image_structural(
+        img: &RgbaImage,
+        cols: u16,
+        rows: u16,
+        ramp: &Ramp,
+    ) -> io::Result<Self> {
+        let (cols, rows) = grid_size(img, cols, rows)?;
+
+        // the ramp's coverage stretched to 0.0..=1.0
+        let ink: Vec<f32> = ramp.chars().iter().map(|&c| coverage(c)).collect();
+        let (lo, hi) = ink
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        let pick = |brightness: f32| {
+            if hi <= lo {
+                return ramp.char_for(brightness);
+            }
+            let mut best = (f32::MAX, ' ');
+            for (&c, &v) in ramp.chars().iter().zip(&ink) {
+                let dist = ((v - lo) / (hi - lo) - brightness).abs();
+                if dist < best.0 {
+                    best = (dist, c);
+                }
+            }
+            best.1
+        };
+
+        let (sub_cols, sub_rows) = (2 * cols as u32, 2 * rows as u32);
+        let mut pixels = Vec::with_capacity(cols as usize * rows as usize);
+        for row in 0..rows as u32 {
+            for col in 0..cols as u32 {
+                let (r, g, b) = average_block(img, cols as u32, rows as u32, col, row);
+                // quadrants: top left, top right, bottom left, bottom right
+                let q = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dx, dy)| {
+                    let (r, g, b) =
+                        average_block(img, sub_cols, sub_rows, 2 * col + dx, 2 * row + dy);
+                    luminance(r, g, b)
+                });
+                let gx = (q[1] + q[3] - q[0] - q[2]) / 2.0;
+                let gy = (q[2] + q[3] - q[0] - q[1]) / 2.0;
+
+                let chr = if gx.abs().max(gy.abs()) < EDGE_THRESHOLD {
+                    pick(luminance(r, g, b))
+                } else if gx.abs() > 2.0 * gy.abs() {
+                    '|'
+                } else if gy.abs() > 2.0 * gx.abs() {
+                    '-'
+                } else if gx * gy > 0.0 {
+                    // brighter towards the top left or bottom right corner
+                    '/'
+                } else {
+                    '\\'
+                };
+                pixels.push(TerminalChar::with_fg(chr, Color::rgb(r, g, b)));
+            }
+        }
+        Self::new(cols, rows, pixels)
+    }
+
+    fn convert_image(
+        img: &RgbaImage,
+        cols: u16,
+        rows: u16,
+        ramp: &Ramp,
+        dither: bool,
+    ) -> io::Result<Self> {
+        let (cols, rows) = grid_size(img, cols, rows)?;
+
+        let (w, h) = (cols as usize, rows as usize);
+        let mut colours = Vec::with_capacity(w * h);
+        for row in 0..rows {
+            for col in 0..cols {
+                colours.push(average_block(
+                    img,
+                    cols as u32,
+                    rows as u32,
+                    col as u32,
+                    row as u32,
+                ));
+            }
+        }
+
+        // luminance scaled to ramp indices
+        let steps = ramp.max_level();
+        let mut levels: Vec<f32> = colours
+            .iter()
+            .map(|&(r, g, b)| luminance(r, g, b) * steps)
+            .collect();
+
+        let mut pixels = Vec::with_capacity(w * h);
+        for i in 0..w * h {
+            let level = levels[i].clamp(0.0, steps);
+            let idx = level.round();
+            if dither {
+                let err = level - idx;
+                let (x, y) = (i % w, i / w);
+                let mut spread = |dx: isize, dy: usize, weight: f32| {
+                    let nx = x as isize + dx;
+                    if nx >= 0 && (nx as usize) < w && y + dy < h {
+                        levels[(y + dy) * w + nx as usize] += err * weight;
+                    }
+                };
+                spread(1, 0, 7.0 / 16.0);
+                spread(-1, 1, 3.0 / 16.0);
+                spread(0, 1, 5.0 / 16.0);
+                spread(1, 1, 1.0 / 16.0);
+            }
+            let (r, g, b) = colours[i];
+            pixels.push(TerminalChar::with_fg(
+                ramp.char_at_level(idx),
+                Color::rgb(r, g, b),
+            ));
+        }
+        Self::new(cols, rows, pixels)
+    }
+}
+
+/// Validate the requested grid and resolve `rows == 0` from the aspect ratio.
+fn grid_size(img: &RgbaImage, cols: u16, rows: u16) -> io::Result<(u16, u16)> {
+    if img.width() == 0 || img.height() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot convert an empty image",
+        ));
+    }
+    if cols == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "column count must be non-zero",
+        ));
+    }
+    if rows != 0 {
+        return Ok((cols, rows));
+    }
+    let rows = cols as f32 * img.height() as f32 / img.width() as f32 / CELL_ASPECT;
+    Ok((cols, rows.round().clamp(1.0, u16::MAX as f32) as u16))
+}
+
+/// Pixel span `[start, end)` covered by cell `i` of `n` along an axis of `len`
+/// pixels, always at least one pixel wide.
+fn span(len: u32, n: u32, i: u32) -> (u32, u32) {
+    let start = (i as u64 * len as u64 / n as u64) as u32;
+    let end = ((i as u64 + 1) * len as u64 / n as u64) as u32;
+    let start = start.min(len - 1);
+    (start, end.max(start + 1))
+}
+
+/// Average colour of the image block under a grid cell, alpha-weighted against black.
+fn average_block(img: &RgbaImage, cols: u32, rows: u32, col: u32, row: u32) -> (u8, u8, u8) {
+    let (x0, x1) = span(img.width(), cols, col);
+    let (y0, y1) = span(img.height(), rows, row);
+    let mut sum = [0u64; 3];
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let [r, g, b, a] = img.get_pixel(x, y);
+            sum[0] += r as u64 * a as u64;
+            sum[1] += g as u64 * a as u64;
+            sum[2] += b as u64 * a as u64;
+        }
+    }
+    let n = (x1 - x0) as u64 * (y1 - y0) as u64 * 255;
+    ((sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8)
+}
+
+/// Perceived brightness in `0.0..=1.0` (Rec. 601 weights).
+pub(crate) fn luminance(r: u8, g: u8, b: u8) -> f32 {
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(chars: &str) -> Ramp {
+        chars.parse().unwrap()
+    }
+
+    fn gradient(width: u32, height: u32) -> RgbaImage {
+        let mut img = RgbaImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let v = (x * 255 / (width - 1)) as u8;
+                img.put_pixel(x, y, [v, v, v, 255]);
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_from_image_ramp_and_colour() {
+        let img = gradient(10, 4);
+        let sprite = AsciiSprite::from_image(&img, 2, 1, &ramp(" @")).unwrap();
+        assert_eq!((sprite.width, sprite.height), (2, 1));
+        assert_eq!(sprite.pixels[0].chr, ' ');
+        assert_eq!(sprite.pixels[1].chr, '@');
+        let (r, g, b) = sprite.pixels[1].fg_color.unwrap().rgb;
+        assert!(r > 150 && r == g && g == b);
+    }
+
+    #[test]
+    fn test_from_image_edge_cases() {
+        let img = gradient(40, 20);
+        // auto rows from aspect ratio
+        let sprite = AsciiSprite::from_image(&img, 20, 0, &ramp(" .:#")).unwrap();
+        assert_eq!(sprite.height, 5);
+        // more cells than pixels still works
+        let sprite = AsciiSprite::from_image(&gradient(2, 2), 8, 8, &ramp(" #")).unwrap();
+        assert_eq!(sprite.pixels.len(), 64);
+
+        assert!(AsciiSprite::from_image(&img, 0, 4, &ramp(" #")).is_err());
+        assert!(AsciiSprite::from_image(&RgbaImage::new(0, 0), 4, 4, &ramp(" #")).is_err());
+        // single cell rows and columns stay in bounds
+        AsciiSprite::from_image_dithered(&img, 1, 6, &ramp(" #")).unwrap();
+        AsciiSprite::from_image_dithered(&img, 6, 1, &ramp(" #")).unwrap();
+    }
+
+    #[test]
+    fn test_from_image_structural() {
+        // a white square on black: vertical, horizontal and diagonal edges
+        let mut img = RgbaImage::new(8, 8);
+        img.fill_rect(2, 2, 4, 4, [255, 255, 255, 255]);
+        let sprite = AsciiSprite::from_image_structural(&img, 8, 8, &Ramp::classic()).unwrap();
+        let rows: Vec<String> = sprite.to_plain_string().lines().map(String::from).collect();
+        assert_eq!(rows[1], "        ");
+        assert_eq!(&rows[3][1..7], " @@@@ ");
+
+        let sprite = AsciiSprite::from_image_structural(&img, 4, 4, &Ramp::classic()).unwrap();
+        assert_eq!(sprite.to_plain_string(), "    \n @@ \n @@ \n    ");
+        let sprite = AsciiSprite::from_image_structural(&img, 2, 2, &Ramp::classic()).unwrap();
+        assert_eq!(sprite.to_plain_string(), "/\\\n\\/");
+        let half = AsciiSprite::from_image_structural(&gradient(2, 2), 1, 1, &ramp(" @"));
+        assert_eq!(half.unwrap().pixels[0].chr, '|');
+
+        // coverage, not the position in the ramp, picks the character
+        let grey = {
+            let mut img = RgbaImage::new(2, 2);
+            img.fill_rect(0, 0, 2, 2, [200, 200, 200, 255]);
+            img
+        };
+        let sprite = AsciiSprite::from_image_structural(&grey, 1, 1, &ramp(" .'@")).unwrap();
+        assert_eq!(sprite.pixels[0].chr, '@');
+        let plain = AsciiSprite::from_image(&grey, 1, 1, &ramp(" .'@")).unwrap();
+        assert_eq!(plain.pixels[0].chr, '\'');
+        assert!(AsciiSprite::from_image_structural(&grey, 0, 1, &ramp(" #")).is_err());
+    }
+
+    #[test]
+    fn test_from_image_halfblock() {
+        // red above blue, 3 pixel rows so the last cell has no lower half
+        let mut img = RgbaImage::new(2, 3);
+        img.fill_rect(0, 0, 2, 1, [255, 0, 0, 255]);
+        img.fill_rect(0, 1, 2, 2, [0, 0, 255, 255]);
+
+        let sprite = AsciiSprite::from_image_halfblock(&img, 2, 0).unwrap();
+        assert_eq!((sprite.width, sprite.height), (2, 2));
+        assert_eq!(
+            sprite.pixels[0],
+            TerminalChar::with_colors('▀', Color::Red, Color::Blue)
+        );
+        assert_eq!(
+            sprite.pixels[3],
+            TerminalChar::with_colors('▀', Color::Blue, Color::Black)
+        );
+
+        let sprite = AsciiSprite::from_image_halfblock(&img, 1, 3).unwrap();
+        assert_eq!((sprite.width, sprite.height), (1, 3));
+        assert_eq!(sprite.pixels[0].fg_color, Some(Color::Red));
+        assert_eq!(sprite.pixels[2].bg_color, Some(Color::Blue));
+        assert!(AsciiSprite::from_image_halfblock(&img, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_dithering_follows_gradient() {
+        // a two character ramp can only threshold, dithering mixes the two
+        // so that each column's density tracks the gradient
+        let img = gradient(64, 16);
+        let ink = |sprite: &AsciiSprite, col: usize| {
+            let cells = sprite.pixels.iter().skip(col).step_by(16);
+            cells.filter(|c| c.chr == '#').count()
+        };
+        let flat = AsciiSprite::from_image(&img, 16, 16, &ramp(" #")).unwrap();
+        let dithered = AsciiSprite::from_image_dithered(&img, 16, 16, &ramp(" #")).unwrap();
+
+        assert_eq!(ink(&flat, 4), 0);
+        assert_eq!(ink(&flat, 11), 16);
+        let inks: Vec<_> = (0..16).map(|col| ink(&dithered, col)).collect();
+        assert!(inks[4] > 2 && inks[4] < 8, "{:?}", inks);
+        assert!(inks[11] > 8 && inks[11] < 14, "{:?}", inks);
+        assert!(inks[0] <= 1 && inks[15] >= 15, "{:?}", inks);
+    }
+}
@@ -0,0 +1,203 @@
+//! The I/O types used by the codecs.
+//!
+//! With the `std` feature (the default) this is simply `std::io`. Without it,
+//! a minimal stand-in with the same names provides `Read`/`Write` for byte
+//! slices and `Vec<u8>`, so sprites can be encoded and decoded in memory on
+//! `no_std + alloc` targets.
+
+#[cfg(feature = "std")]
+pub use std::io::*;
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::*;
+
+use byteorder::ByteOrder;
+
+/// Little helpers for reading fixed-size integers, mirroring `byteorder::ReadBytesExt`.
+pub(crate) trait ReadBytesExt: Read {
+    fn read_u8(&mut self) -> Result<u8> {
+        let mut b = [0; 1];
+        self.read_exact(&mut b)?;
+        Ok(b[0])
+    }
+
+    fn read_u16<T: ByteOrder>(&mut self) -> Result<u16> {
+        let mut b = [0; 2];
+        self.read_exact(&mut b)?;
+        Ok(T::read_u16(&b))
+    }
+
+    fn read_u32<T: ByteOrder>(&mut self) -> Result<u32> {
+        let mut b = [0; 4];
+        self.read_exact(&mut b)?;
+        Ok(T::read_u32(&b))
+    }
+
+    fn read_f32<T: ByteOrder>(&mut self) -> Result<f32> {
+        let mut b = [0; 4];
+        self.read_exact(&mut b)?;
+        Ok(T::read_f32(&b))
+    }
+}
+
+impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+/// Little helpers for writing fixed-size integers, mirroring `byteorder::WriteBytesExt`.
+pub(crate) trait WriteBytesExt: Write {
+    fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.write_all(&[n])
+    }
+
+    fn write_u16<T: ByteOrder>(&mut self, n: u16) -> Result<()> {
+        let mut b = [0; 2];
+        T::write_u16(&mut b, n);
+        self.write_all(&b)
+    }
+
+    fn write_u32<T: ByteOrder>(&mut self, n: u32) -> Result<()> {
+        let mut b = [0; 4];
+        T::write_u32(&mut b, n);
+        self.write_all(&b)
+    }
+
+    fn write_f32<T: ByteOrder>(&mut self, n: f32) -> Result<()> {
+        let mut b = [0; 4];
+        T::write_f32(&mut b, n);
+        self.write_all(&b)
+    }
+}
+
+impl<W: Write + ?Sized> WriteBytesExt for W {}
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use alloc::{string::String, vec::Vec};
+    use core::fmt;
+
+    /// The subset of `std::io::ErrorKind` this crate reports.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        InvalidInput,
+        InvalidData,
+        UnexpectedEof,
+        WriteZero,
+        Other,
+    }
+
+    impl fmt::Display for ErrorKind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let s = match self {
+                ErrorKind::InvalidInput => "invalid input parameter",
+                ErrorKind::InvalidData => "invalid data",
+                ErrorKind::UnexpectedEof => "unexpected end of file",
+                ErrorKind::WriteZero => "write zero",
+                ErrorKind::Other => "other error",
+            };
+            f.write_str(s)
+        }
+    }
+
+    /// An error kind together with an optional message, like `std::io::Error`.
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: Option<String>,
+    }
+
+    impl Error {
+        pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Self {
+            Self {
+                kind,
+                message: Some(message.into()),
+            }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Self {
+                kind,
+                message: None,
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.message {
+                Some(m) => f.write_str(m),
+                None => fmt::Display::fmt(&self.kind, f),
+            }
+        }
+    }
+
+    impl core::error::Error for Error {}
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(ErrorKind::WriteZero.into()),
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = buf.len().min(self.len());
+            let (head, tail) = self.split_at(n);
+            buf[..n].copy_from_slice(head);
+            *self = tail;
+            Ok(n)
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+    }
+}
@@ -1,14 +1,106 @@
+//! Terminal sprites and videos: coloured character grids with a compact
+//! binary file format.
+//!
+//! Everything except file access, compression, GIF export, image import and
+//! parallel decoding works without the default `std` feature, on
+//! `no_std + alloc`. [`io`] then provides the `Read`/`Write` traits the codecs
+//! use.
+//!
+//! Optional features, all needing `std`:
+//! - `gzip` (default): reading and writing gzip compressed video files.
+//!   Without it, reading such a file fails with `ErrorKind::Unsupported`.
+//! - `parallel`: `AsciiVideo::read_from_file_parallel`, which decodes
+//!   frames on all cores.
+//!
+//! `byteorder` is the only dependency. DEFLATE/gzip, PNG and GIF encoding,
+//! the RGBA image buffer and the decoding threads (`std::thread::scope`) are
+//! all small in-crate implementations, so no feature pulls in further crates.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::{
+    fmt,
+    ops::{Deref, DerefMut, Index, IndexMut, Range},
+    time::Duration,
+};
+#[cfg(feature = "std")]
 use std::{
     fs::File,
-    io::{self, BufReader, BufWriter, Read, Write},
+    io::{BufRead, BufReader, BufWriter},
+    path::Path,
 };
 
+pub mod codec;
+pub mod io;
+use io::{Read, ReadBytesExt, Write, WriteBytesExt};
+
 pub mod colour;
-pub use colour::Color;
+pub use colour::{Color, DistanceMetric};
+
+pub use ansi::ColorDepth;
+pub use borrowed::BorrowedSprite;
+pub use builder::AsciiVideoBuilder;
+pub use decoder::AsciiVideoDecoder;
+pub use diff::FrameDiff;
+pub use ramp::Ramp;
+pub use sheet::AsciiSpriteSheet;
+pub use transform::{Anchor, BlendMode, WrapMode};
+pub use transition::TransitionKind;
+
+pub mod raster;
+pub use raster::RgbaImage;
+#[cfg(feature = "std")]
+pub use video_file::AsciiVideoFile;
+#[cfg(feature = "std")]
+pub use writer::AsciiVideoWriter;
+
+mod ansi;
+mod ansi_art;
+mod borrowed;
+mod builder;
+mod decoder;
+#[cfg(feature = "std")]
+mod deflate;
+mod density;
+mod diff;
+mod draw;
+#[cfg(feature = "std")]
+mod gif;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod header;
+#[cfg(feature = "std")]
+mod import;
+mod noise;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "std")]
+mod png;
+mod ramp;
+mod sheet;
+#[cfg(feature = "std")]
+mod sprite_file;
+mod text;
+mod transform;
+mod transition;
+#[cfg(feature = "std")]
+mod video_file;
+mod width;
+#[cfg(feature = "std")]
+mod writer;
+
+use byteorder::LittleEndian;
 
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 /// A single character together with optional foreground / background colours
-#[derive(Debug, PartialEq, Clone, Copy)]
+///
+/// Equality compares the stored values: a cell without colour and one with
+/// [`Color::reset`] colours look the same on screen, but aren't equal, and a
+/// reset colour never equals black either. Compare
+/// [`TerminalChar::normalized`] cells when only the looks matter.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct TerminalChar {
     pub chr: char,
     pub fg_color: Option<Color>,
@@ -27,15 +119,53 @@ impl From<(char, Color)> for TerminalChar {
     }
 }
 
-impl TerminalChar {
-    fn default() -> Self {
+impl From<(char, Color, Color)> for TerminalChar {
+    fn from((chr, fg, bg): (char, Color, Color)) -> Self {
+        TerminalChar::with_colors(chr, fg, bg)
+    }
+}
+
+impl From<(char, Option<Color>, Option<Color>)> for TerminalChar {
+    fn from((chr, fg_color, bg_color): (char, Option<Color>, Option<Color>)) -> Self {
         Self {
-            chr: ' ',
-            fg_color: None,
-            bg_color: None,
+            chr,
+            fg_color,
+            bg_color,
         }
     }
+}
+
+impl Default for TerminalChar {
+    /// [`TerminalChar::BLANK`]
+    fn default() -> Self {
+        Self::BLANK
+    }
+}
+
+impl TerminalChar {
+    /// The blank cell: a space without colours. Overlays treat it as
+    /// transparent, and it fills the space sprites are enlarged by.
+    pub const BLANK: TerminalChar = TerminalChar {
+        chr: ' ',
+        fg_color: None,
+        bg_color: None,
+    };
+
+    /// True if this is exactly [`TerminalChar::BLANK`].
+    ///
+    /// A space with reset colours isn't blank, as reset colours are stored
+    /// as such; use `c.normalized().is_blank()` to also accept those and
+    /// other cells that merely look blank, like a coloured space on the
+    /// default background.
+    pub fn is_blank(&self) -> bool {
+        *self == Self::BLANK
+    }
 
+    /// Create a TerminalChar without colours.
+    ///
+    /// Any character is accepted, including control codes like `'\n'` or
+    /// `'\x1b'` that break rendering, use [`TerminalChar::try_from_char`]
+    /// for untrusted input.
     pub fn from_char<C: Into<char>>(chr: C) -> Self {
         Self {
             chr: chr.into(),
@@ -44,6 +174,59 @@ impl TerminalChar {
         }
     }
 
+    /// Create a TerminalChar without colours, rejecting control characters.
+    ///
+    /// ## Error
+    /// if `chr` is a C0 or C1 control character (including DEL)
+    pub fn try_from_char(chr: char) -> io::Result<Self> {
+        if chr.is_control() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("control character {:?} can't be displayed", chr),
+            ));
+        }
+        Ok(Self::from_char(chr))
+    }
+
+    /// True unless the character is a C0 or C1 control character.
+    pub fn is_printable(&self) -> bool {
+        !self.chr.is_control()
+    }
+
+    /// Compare only the characters, ignoring both colours.
+    pub fn eq_ignore_color(&self, other: &TerminalChar) -> bool {
+        self.chr == other.chr
+    }
+
+    /// The canonical form of a character that looks the same, see
+    /// [`AsciiSprite::normalize`].
+    pub fn normalized(self) -> Self {
+        let keep = |col: Option<Color>| col.filter(|col| !col.reset);
+        Self {
+            chr: self.chr,
+            fg_color: keep(self.fg_color).filter(|_| !self.chr.is_whitespace()),
+            bg_color: keep(self.bg_color),
+        }
+    }
+
+    /// Exchange the foreground and background colours, including unset
+    /// ones, like the `reverse` attribute of a terminal.
+    pub fn swap_colors(self) -> Self {
+        Self {
+            chr: self.chr,
+            fg_color: self.bg_color,
+            bg_color: self.fg_color,
+        }
+    }
+
+    /// Number of terminal columns the character occupies.
+    ///
+    /// 2 for wide characters like CJK ideographs and most emoji,
+    /// 0 for combining marks and control characters, otherwise 1.
+    pub fn display_width(&self) -> usize {
+        width::char_width(self.chr)
+    }
+
     pub fn set_fg(mut self, fg: Color) -> Self {
         self.fg_color = Some(fg);
         self
@@ -91,79 +274,134 @@ impl TerminalChar {
         self.bg_color.and_then(|c| c.as_ansi256())
     }
 
+    /// Convert the foreground colour to an ANSI-16 code if possible.
+    pub fn fg_to_ansi16(&self) -> Option<u8> {
+        self.fg_color.and_then(|c| c.as_ansi16())
+    }
+
+    /// Convert the background colour to an ANSI-16 code if possible.
+    pub fn bg_to_ansi16(&self) -> Option<u8> {
+        self.bg_color.and_then(|c| c.as_ansi16())
+    }
+
+    /// Largest number of bytes [`TerminalChar::write_to`] writes for a
+    /// character, the one with both colours set. See
+    /// [`TerminalChar::serialized_size`] for the size of a given character.
+    pub const SERIALIZED_SIZE: usize = 4 + 2 * 4;
+
+    /// Number of bytes [`TerminalChar::write_encoded`] writes for this
+    /// character: 1 to 3 (varint) or 4 (fixed) for the code point, then 1 per
+    /// colour, plus 3 if it is set.
+    pub fn serialized_size(&self, encoding: CharEncoding) -> usize {
+        self.encoded_size(encoding, false)
+    }
+
+    /// Like [`TerminalChar::serialized_size`], with an exact ANSI-256
+    /// colour taking 2 bytes if `indexed`, see [`codec::write_cell`].
+    pub(crate) fn encoded_size(&self, encoding: CharEncoding, indexed: bool) -> usize {
+        let code = match encoding {
+            CharEncoding::Fixed => 4,
+            CharEncoding::Varint => match self.chr as u32 {
+                0..0x80 => 1,
+                0x80..0x4000 => 2,
+                _ => 3,
+            },
+        };
+        let colour = |col: Option<Color>| match col {
+            Some(col) if indexed && col.ansi256().is_some() => 2,
+            Some(col) if !col.reset => 4,
+            _ => 1,
+        };
+        code + colour(self.fg_color) + colour(self.bg_color)
+    }
+
     /// Write a character to the writer
     ///   u32 little-endian code point
     ///   u8 flag + 3×u8 for optional foreground RGB
     ///   u8 flag + 3×u8 for optional background RGB
+    ///
+    /// Colours are always stored as RGB, palette indices are only written
+    /// inside videos, whose header marks them.
     pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        w.write_u32::<LittleEndian>(self.chr as u32)?;
-
-        // Foreground colour
-        if let Some(col) = self.fg_color {
-            if !col.reset {
-                w.write_u8(1)?;
-                let (r, g, b) = col.rgb;
-                w.write_u8(r)?;
-                w.write_u8(g)?;
-                w.write_u8(b)?;
-            } else {
-                w.write_u8(0)?;
-            }
-        } else {
-            w.write_u8(0)?;
-        }
+        self.write_encoded(w, CharEncoding::Fixed)
+    }
 
-        // Background colour
-        if let Some(col) = self.bg_color {
-            if !col.reset {
-                w.write_u8(1)?;
-                let (r, g, b) = col.rgb;
-                w.write_u8(r)?;
-                w.write_u8(g)?;
-                w.write_u8(b)?;
-            } else {
-                w.write_u8(0)?;
-            }
-        } else {
-            w.write_u8(0)?;
+    /// Check that the character can be stored and exchanged safely.
+    ///
+    /// A `char` is never a surrogate, so every code point written is a
+    /// valid Unicode scalar value that [`TerminalChar::read_from`] accepts.
+    /// On top of that, the 66 noncharacters (`U+FDD0..=U+FDEF` and the last
+    /// two code points of every plane, like `U+FFFE`) are rejected: they are
+    /// reserved for internal use and terminals handle them inconsistently.
+    /// Unassigned code points are accepted, as they may be assigned by a
+    /// newer Unicode version. All writers of the crate call this first.
+    ///
+    /// ## Error
+    /// `InvalidData` if the character is a noncharacter
+    pub fn validate(&self) -> io::Result<()> {
+        let code = self.chr as u32;
+        if (0xFDD0..=0xFDEF).contains(&code) || code & 0xFFFE == 0xFFFE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("noncharacter U+{:04X} can't be stored", code),
+            ));
         }
-
         Ok(())
     }
 
+    /// Write a character like [`TerminalChar::write_to`], storing the
+    /// code point as `encoding` specifies.
+    ///
+    /// ## Error
+    /// if [`TerminalChar::validate`] rejects the character or writing fails
+    pub fn write_encoded<W: Write>(&self, w: &mut W, encoding: CharEncoding) -> io::Result<()> {
+        self.write_with(w, encoding, false)
+    }
+
+    /// Write the character, storing palette indices only if `indexed`,
+    /// see [`codec::write_cell`].
+    pub(crate) fn write_with<W: Write>(
+        &self,
+        w: &mut W,
+        encoding: CharEncoding,
+        indexed: bool,
+    ) -> io::Result<()> {
+        self.validate()?;
+        codec::write_cell(w, self, encoding, indexed)
+    }
+
     /// Read a character from the same binary format.
     pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
-        let code = r.read_u32::<LittleEndian>()?;
-        let chr = std::char::from_u32(code).ok_or_else(|| {
-            io::Error::new(io::ErrorKind::InvalidData, "invalid Unicode scalar value")
-        })?;
+        Self::read_encoded(r, CharEncoding::Fixed)
+    }
 
-        // Foreground colour
-        let fg_color = if r.read_u8()? == 1 {
-            let r8 = r.read_u8()?;
-            let g8 = r.read_u8()?;
-            let b8 = r.read_u8()?;
-            Some(Color::rgb(r8, g8, b8))
-        } else {
-            None
-        };
+    /// Read a character written by [`TerminalChar::write_encoded`].
+    pub fn read_encoded<R: Read>(r: &mut R, encoding: CharEncoding) -> io::Result<Self> {
+        codec::read_cell(r, encoding)
+    }
+}
 
-        // Background colour
-        let bg_color = if r.read_u8()? == 1 {
-            let r8 = r.read_u8()?;
-            let g8 = r.read_u8()?;
-            let b8 = r.read_u8()?;
-            Some(Color::rgb(r8, g8, b8))
-        } else {
-            None
-        };
+/// How the code point of every [`TerminalChar`] is stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharEncoding {
+    /// A `u32` little-endian, 4 bytes per character.
+    #[default]
+    Fixed,
+    /// LEB128: 7 bits per byte, low bits first, the high bit set on all but
+    /// the last byte. ASCII takes 1 byte, every other char at most 3.
+    /// 300 frames of 80x24 ASCII text shrink by a third with a foreground
+    /// colour on every cell (5.2 MB to 3.5 MB) and by half without colours.
+    Varint,
+}
 
-        Ok(Self {
-            chr,
-            fg_color,
-            bg_color,
-        })
-    }
+/// Layout of the grid returned by [`AsciiSprite::as_grid_with`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// One vector per row, indexed `grid[y][x]`.
+    #[default]
+    RowMajor,
+    /// One vector per column, indexed `grid[x][y]`.
+    ColumnMajor,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -177,7 +415,7 @@ impl FromIterator<TerminalChar> for TerminalString {
 
 impl IntoIterator for TerminalString {
     type Item = TerminalChar;
-    type IntoIter = std::vec::IntoIter<TerminalChar>;
+    type IntoIter = alloc::vec::IntoIter<TerminalChar>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -186,7 +424,7 @@ impl IntoIterator for TerminalString {
 
 impl<'a> IntoIterator for &'a TerminalString {
     type Item = &'a TerminalChar;
-    type IntoIter = std::slice::Iter<'a, TerminalChar>;
+    type IntoIter = core::slice::Iter<'a, TerminalChar>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter()
@@ -195,13 +433,104 @@ impl<'a> IntoIterator for &'a TerminalString {
 
 impl<'a> IntoIterator for &'a mut TerminalString {
     type Item = &'a mut TerminalChar;
-    type IntoIter = std::slice::IterMut<'a, TerminalChar>;
+    type IntoIter = core::slice::IterMut<'a, TerminalChar>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter_mut()
     }
 }
 
+impl Deref for TerminalString {
+    type Target = [TerminalChar];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TerminalString {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Renders the string with ANSI truecolor escape sequences,
+/// resetting the terminal colours at the end.
+impl fmt::Display for TerminalString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut state = ansi::SgrState::default();
+        for c in &self.0 {
+            state.write_char(f, c)?;
+        }
+        state.finish(f)
+    }
+}
+
+impl TerminalString {
+    const MAX_LEN: usize = 1_000_000;
+
+    /// Render with ANSI escape sequences for a terminal supporting `depth`,
+    /// colours are converted to the nearest one available.
+    /// `to_string` renders with [`ColorDepth::TrueColor`].
+    pub fn to_ansi(&self, depth: ColorDepth) -> String {
+        let mut out = String::new();
+        let mut state = ansi::SgrState::new(depth);
+        for c in &self.0 {
+            let _ = state.write_char(&mut out, c);
+        }
+        let _ = state.finish(&mut out);
+        out
+    }
+
+    /// Return only the characters, dropping all colours.
+    pub fn as_plain_string(&self) -> String {
+        self.0.iter().map(|c| c.chr).collect()
+    }
+
+    /// Append a single character.
+    pub fn push(&mut self, c: TerminalChar) {
+        self.0.push(c);
+    }
+
+    /// Append every character of `s` with default colours.
+    pub fn extend_from_str(&mut self, s: &str) {
+        self.0.extend(s.chars().map(TerminalChar::from));
+    }
+
+    /// Write the string to the writer
+    ///   u32 little-endian character count
+    ///   every character in the `TerminalChar` format
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.0.len() > Self::MAX_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("string too long: {} (max {})", self.0.len(), Self::MAX_LEN),
+            ));
+        }
+        w.write_u32::<LittleEndian>(self.0.len() as u32)?;
+        for c in &self.0 {
+            c.write_to(w)?;
+        }
+        Ok(())
+    }
+
+    /// Read a string from the same binary format.
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        let len = r.read_u32::<LittleEndian>()? as usize;
+        if len > Self::MAX_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("string too long: {} (max {})", len, Self::MAX_LEN),
+            ));
+        }
+        let mut chars = Vec::with_capacity(len);
+        for _ in 0..len {
+            chars.push(TerminalChar::read_from(r)?);
+        }
+        Ok(Self(chars))
+    }
+}
+
 // Convenience: create TerminalString from a &str, all default colors
 impl From<&str> for TerminalString {
     fn from(s: &str) -> Self {
@@ -223,13 +552,14 @@ impl AsciiSprite {
     /// ## Error
     /// if `width * height` doesn't match with the size of the pixel-vector
     pub fn new(width: u16, height: u16, pixels: Vec<TerminalChar>) -> io::Result<Self> {
-        if pixels.len() != (width as usize) * (height as usize) {
+        let cells = cell_count(width, height, io::ErrorKind::InvalidInput)?;
+        if pixels.len() != cells {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 format!(
                     "pixel count {} does not match width*height ({})",
                     pixels.len(),
-                    (width as usize) * (height as usize)
+                    cells
                 ),
             ));
         }
@@ -240,19 +570,146 @@ impl AsciiSprite {
         })
     }
 
+    /// Build a sprite from a snapshot of a terminal screen buffer: one
+    /// `(char, fg, bg)` per cell, row by row.
+    ///
+    /// Reset colours mean "the terminal's default", which is what a cell
+    /// without colour stores, so they become `None`.
+    ///
+    /// ## Error
+    /// if `cells` doesn't hold exactly `width * height` entries
+    pub fn capture_buffer(
+        cells: &[(char, Color, Color)],
+        width: u16,
+        height: u16,
+    ) -> io::Result<Self> {
+        let colour = |c: Color| (!c.reset).then_some(c);
+        let pixels = cells
+            .iter()
+            .map(|&(chr, fg, bg)| TerminalChar {
+                chr,
+                fg_color: colour(fg),
+                bg_color: colour(bg),
+            })
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Stack rows of text into a sprite.
+    ///
+    /// The longest row determines the width, shorter rows are padded with
+    /// blank cells on the right.
+    ///
+    /// ## Error
+    /// if there are no rows, every row is empty, or a dimension exceeds `u16::MAX`
+    pub fn from_rows(rows: Vec<TerminalString>) -> io::Result<Self> {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if width == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot build a sprite from empty rows",
+            ));
+        }
+        let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(rows.len())) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{}x{} rows exceed the maximum sprite size",
+                    width,
+                    rows.len()
+                ),
+            ));
+        };
+
+        let mut pixels = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            let pad = width - row.len();
+            pixels.extend(row);
+            pixels.extend(core::iter::repeat_n(TerminalChar::default(), pad));
+        }
+        Self::new(w, h, pixels)
+    }
+
+    /// Build a sprite from rows of cells, the inverse of
+    /// [`AsciiSprite::as_grid`].
+    ///
+    /// ## Error
+    /// `InvalidInput` if there are no rows, they are empty or have
+    /// different lengths, or a dimension exceeds `u16::MAX`
+    pub fn from_grid(rows: Vec<Vec<TerminalChar>>) -> io::Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot build a sprite from empty rows",
+            ));
+        }
+        if let Some(y) = rows.iter().position(|r| r.len() != width) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "row {} has {} cells but row 0 has {}",
+                    y,
+                    rows[y].len(),
+                    width
+                ),
+            ));
+        }
+        let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(rows.len())) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{}x{} rows exceed the maximum sprite size",
+                    width,
+                    rows.len()
+                ),
+            ));
+        };
+        Self::new(w, h, rows.concat())
+    }
+
+    /// Number of bytes [`AsciiSprite::write_to`] writes for this sprite.
+    pub fn serialized_size(&self) -> usize {
+        self.pixels
+            .iter()
+            .map(|p| p.serialized_size(CharEncoding::Fixed))
+            .sum()
+    }
+
     /// Serialise the sprite
     pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_encoded(w, CharEncoding::Fixed, false)
+    }
+
+    pub(crate) fn write_encoded<W: Write>(
+        &self,
+        w: &mut W,
+        encoding: CharEncoding,
+        indexed: bool,
+    ) -> io::Result<()> {
         for p in &self.pixels {
-            p.write_to(w)?;
+            p.write_with(w, encoding, indexed)?;
         }
         Ok(())
     }
 
     /// Deserialise a sprite given its dimensions
     pub fn read_from<R: Read>(r: &mut R, width: u16, height: u16) -> io::Result<Self> {
-        let mut pixels = Vec::with_capacity((width as usize) * (height as usize));
-        for _ in 0..(width as usize * height as usize) {
-            pixels.push(TerminalChar::read_from(r)?);
+        Self::read_encoded(r, width, height, CharEncoding::Fixed)
+    }
+
+    pub(crate) fn read_encoded<R: Read>(
+        r: &mut R,
+        width: u16,
+        height: u16,
+        encoding: CharEncoding,
+    ) -> io::Result<Self> {
+        let cells = cell_count(width, height, io::ErrorKind::InvalidData)?;
+        // the size comes from the input, so let a short stream fail before
+        // it reserves memory for the whole sprite
+        let mut pixels = Vec::with_capacity(cells.min(1 << 16));
+        for _ in 0..cells {
+            pixels.push(TerminalChar::read_encoded(r, encoding)?);
         }
         Ok(Self {
             width,
@@ -261,148 +718,1604 @@ impl AsciiSprite {
         })
     }
 
-    /// Return the sprites pixel buffer as a two-dimensional grid.
+    /// Return the sprites pixel buffer as a two-dimensional grid of rows.
     pub fn as_grid(&self) -> Vec<Vec<TerminalChar>> {
-        let mut grid = Vec::with_capacity(self.height as usize);
-        for row in 0..self.height {
-            let mut rvec = Vec::with_capacity(self.width as usize);
-            for col in 0..self.width {
-                let idx = (row as usize) * self.width as usize + col as usize;
-                rvec.push(self.pixels[idx]);
-            }
-            grid.push(rvec);
+        self.as_grid_with(Order::RowMajor)
+    }
+
+    /// Return the sprites pixel buffer as a two-dimensional grid, either
+    /// `height` rows or `width` columns, see [`Order`].
+    pub fn as_grid_with(&self, order: Order) -> Vec<Vec<TerminalChar>> {
+        match order {
+            Order::RowMajor => (0..self.height)
+                .map(|y| {
+                    (0..self.width)
+                        .filter_map(|x| self.get_char(x, y))
+                        .collect()
+                })
+                .collect(),
+            Order::ColumnMajor => (0..self.width)
+                .map(|x| {
+                    (0..self.height)
+                        .filter_map(|y| self.get_char(x, y))
+                        .collect()
+                })
+                .collect(),
         }
-        grid
     }
     /// Return the sprites Pixel buffer as a flat vector.
     pub fn as_flat(&self) -> Vec<TerminalChar> {
         self.pixels.clone()
     }
 
-    /// Get a character at the given coordinates, or ``None`` if out of bounds
-    pub fn get_char(&self, x: u16, y: u16) -> Option<TerminalChar> {
-        if x >= self.width || y >= self.height {
-            return None;
+    /// The characters of the sprite without any colours, rows separated by `\n`.
+    pub fn to_plain_string(&self) -> String {
+        let mut out = String::with_capacity(self.pixels.len() + self.height as usize);
+        for (i, row) in self.pixels.chunks(self.width.max(1) as usize).enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.extend(row.iter().map(|c| c.chr));
         }
-        let idx = (y as usize) * self.width as usize + x as usize;
-        Some(self.pixels[idx])
+        out
     }
-}
-
-/// A collection of frames that share the same dimensions.
-#[derive(Debug, PartialEq, Clone)]
-pub struct AsciiVideo {
-    pub width: u16,
-    pub height: u16,
-    pub frames: Vec<AsciiSprite>,
-}
-
-impl AsciiVideo {
-    const MAGIC: [u8; 4] = *b"ASCV";
-    const VERSION: u8 = 1;
 
-    /// Create a new video
-    pub fn new(width: u16, height: u16, frames: Vec<AsciiSprite>) -> io::Result<Self> {
-        for (i, f) in frames.iter().enumerate() {
-            if f.width != width || f.height != height {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!(
-                        "Frame {} has size {}x{} but expected {}x{}",
-                        i, f.width, f.height, width, height
-                    ),
-                ));
+    /// A grid of the characters for debugging, with column indices on top
+    /// and row indices down the side.
+    ///
+    /// Blank cells, whitespace without a visible background, show as `·`,
+    /// other whitespace as `␣` and control characters as `�`, so every
+    /// cell is visible. Colours are left out; use [`AsciiSprite::to_ansi`]
+    /// to see those.
+    ///
+    /// ```text
+    ///   0 1 2
+    /// 0 a · ␣
+    /// 1 · b ·
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let digits = |n: u16| format!("{}", n.saturating_sub(1)).len();
+        let (col_w, row_w) = (digits(self.width), digits(self.height));
+        let mut out = format!("{:row_w$}", "");
+        for x in 0..self.width {
+            out.push_str(&format!(" {:<col_w$}", x));
+        }
+        for (y, row) in self.pixels.chunks(self.width.max(1) as usize).enumerate() {
+            out.push_str(&format!("\n{:>row_w$}", y));
+            for c in row {
+                let shown_bg = c.bg_color.is_some_and(|col| !col.reset);
+                let marker = match c.chr {
+                    chr if chr.is_whitespace() && !shown_bg => '·',
+                    chr if chr.is_whitespace() => '␣',
+                    chr if chr.is_control() => '\u{FFFD}',
+                    chr => chr,
+                };
+                out.push_str(&format!(" {:<col_w$}", marker));
             }
         }
-        Ok(Self {
-            width,
-            height,
-            frames,
-        })
+        out
     }
 
-    /// Return the number of frames and the dimensions.
-    /// (frame_count, height, width)
-    pub fn size(&self) -> (usize, usize, usize) {
-        (self.frames.len(), self.height as usize, self.width as usize)
+    /// Render the sprite with ANSI escape sequences for a terminal supporting
+    /// `depth`, rows separated by `\n`. Colours are reset at the end of every
+    /// row, so backgrounds don't bleed into the rest of the line.
+    pub fn to_ansi(&self, depth: ColorDepth) -> String {
+        let mut out = String::new();
+        let mut state = ansi::SgrState::new(depth);
+        for (i, row) in self.pixels.chunks(self.width.max(1) as usize).enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            for c in row {
+                let _ = state.write_char(&mut out, c);
+            }
+            let _ = state.finish(&mut out);
+        }
+        out
     }
 
-    pub fn write_to_file(&self, path: &str) -> io::Result<()> {
-        let f = File::create(path)?;
-        let mut w = BufWriter::new(f);
+    /// Iterate over every cell with its coordinates, row by row.
+    pub fn enumerate_cells(&self) -> impl Iterator<Item = (u16, u16, &TerminalChar)> {
+        let width = self.width as usize;
+        self.pixels
+            .iter()
+            .enumerate()
+            .map(move |(i, c)| ((i % width) as u16, (i / width) as u16, c))
+    }
 
-        // Header
-        w.write_all(&Self::MAGIC)?;
-        w.write_u8(Self::VERSION)?;
-        w.write_u16::<LittleEndian>(self.width)?;
-        w.write_u16::<LittleEndian>(self.height)?;
-        w.write_u32::<LittleEndian>(self.frames.len() as u32)?;
+    /// Like [`AsciiSprite::enumerate_cells`], but the cells can be modified.
+    pub fn enumerate_cells_mut(&mut self) -> impl Iterator<Item = (u16, u16, &mut TerminalChar)> {
+        let width = self.width as usize;
+        self.pixels
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, c)| ((i % width) as u16, (i / width) as u16, c))
+    }
 
-        // Frames
-        for f in &self.frames {
-            f.write_to(&mut w)?;
+    /// Position of the cell at the given coordinates in [`AsciiSprite::pixels`],
+    /// or `None` if out of bounds.
+    pub fn index_of(&self, x: u16, y: u16) -> Option<usize> {
+        if y >= self.height {
+            return None;
         }
-
-        w.flush()
+        index(x, y, self.width)
     }
 
-    pub fn read_from_file(path: &str) -> io::Result<Self> {
-        let f = File::open(path)?;
-        let mut r = BufReader::new(f);
+    /// Get a character at the given coordinates, or ``None`` if out of bounds
+    pub fn get_char(&self, x: u16, y: u16) -> Option<TerminalChar> {
+        self.index_of(x, y).map(|idx| self.pixels[idx])
+    }
 
-        // Header
-        let mut magic = [0u8; 4];
-        r.read_exact(&mut magic)?;
-        if magic != Self::MAGIC {
+    /// Like [`AsciiSprite::get_char`], but reports which coordinate is out of bounds.
+    ///
+    /// ## Error
+    /// `InvalidInput` if `x` or `y` is outside the sprite
+    pub fn try_get_char(&self, x: u16, y: u16) -> io::Result<TerminalChar> {
+        if x >= self.width {
             return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "bad magic number",
+                io::ErrorKind::InvalidInput,
+                format!("x {} out of range for width {}", x, self.width),
             ));
         }
-
-        let ver = r.read_u8()?;
-        if ver != Self::VERSION {
+        if y >= self.height {
             return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("unsupported version {}", ver),
+                io::ErrorKind::InvalidInput,
+                format!("y {} out of range for height {}", y, self.height),
             ));
         }
+        Ok(self.pixels[index(x, y, self.width).expect("checked above")])
+    }
 
-        let width = r.read_u16::<LittleEndian>()?;
-        let height = r.read_u16::<LittleEndian>()?;
-        let frame_count = r.read_u32::<LittleEndian>()? as usize;
+    /// Replace the character at the given coordinates.
+    /// Returns `false` and changes nothing if they are out of bounds.
+    pub fn set_char(&mut self, x: u16, y: u16, c: TerminalChar) -> bool {
+        let Some(idx) = self.index_of(x, y) else {
+            return false;
+        };
+        self.pixels[idx] = c;
+        true
+    }
 
-        if width == 0 || height == 0 || width > 4096 || height > 4096 {
-            return Err(io::Error::new(
+    /// Set every cell to `c`.
+    pub fn fill(&mut self, c: TerminalChar) {
+        self.pixels.fill(c);
+    }
+
+    /// Replace every foreground and background colour equal to `from` with `to`.
+    /// Returns the number of colours replaced, counting fg and bg separately.
+    pub fn replace_color(&mut self, from: Color, to: Color) -> usize {
+        let mut count = 0;
+        for p in &mut self.pixels {
+            for col in [&mut p.fg_color, &mut p.bg_color] {
+                if *col == Some(from) {
+                    *col = Some(to);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Scale the brightness of every colour by `factor`, see
+    /// [`Color::scale_brightness`]. Stepping `factor` from 1.0 to 0.0 over a
+    /// few frames fades the sprite out to black.
+    pub fn adjust_brightness(&mut self, factor: f32) {
+        for p in &mut self.pixels {
+            for col in [&mut p.fg_color, &mut p.bg_color].into_iter().flatten() {
+                *col = col.scale_brightness(factor);
+            }
+        }
+    }
+
+    /// Blend every colour towards `tint` by `strength` (0.0 to 1.0), see
+    /// [`Color::blend`]. A brownish tint like `Color::rgb(112, 66, 20)` at
+    /// about 0.4 gives a sepia look. Reset colours are left alone.
+    pub fn tint(&mut self, tint: Color, strength: f32) {
+        for p in &mut self.pixels {
+            let colours = [&mut p.fg_color, &mut p.bg_color].into_iter().flatten();
+            for col in colours.filter(|col| !col.reset) {
+                *col = col.blend(tint, strength);
+            }
+        }
+    }
+
+    /// Replace every `from` character with `to`, keeping the colours.
+    /// Returns the number of cells changed.
+    pub fn replace_char(&mut self, from: char, to: char) -> usize {
+        let mut count = 0;
+        for p in self.pixels.iter_mut().filter(|p| p.chr == from) {
+            p.chr = to;
+            count += 1;
+        }
+        count
+    }
+
+    /// Bring every cell into a canonical form, so sprites that look the same
+    /// also compare equal:
+    /// - reset colours become `None`, as both mean the terminal default
+    /// - whitespace loses its foreground colour, which never shows
+    ///
+    /// Files store reset colours as absent, so reading a sprite back applies
+    /// the first rule already. A normalized sprite therefore round-trips
+    /// through [`AsciiSprite::write_to`] unchanged, and for any sprite,
+    /// normalizing before or after the round trip gives the same result.
+    pub fn normalize(&mut self) {
+        for p in &mut self.pixels {
+            *p = p.normalized();
+        }
+    }
+
+    /// Exchange foreground and background colours of every cell, see
+    /// [`TerminalChar::swap_colors`].
+    pub fn swap_colors(&mut self) {
+        for p in &mut self.pixels {
+            *p = p.swap_colors();
+        }
+    }
+
+    /// True if both sprites have the same dimensions and characters,
+    /// whatever their colours.
+    pub fn eq_ignore_color(&self, other: &AsciiSprite) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .pixels
+                .iter()
+                .zip(&other.pixels)
+                .all(|(a, b)| a.eq_ignore_color(b))
+    }
+
+    /// Number of terminal columns the sprite occupies when rendered:
+    /// the widest character of every column, summed over all columns.
+    pub fn visual_width(&self) -> usize {
+        (0..self.width as usize)
+            .map(|x| {
+                self.pixels
+                    .iter()
+                    .skip(x)
+                    .step_by(self.width as usize)
+                    .map(|c| c.display_width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+
+    /// Number of cells that aren't a space without colours.
+    pub fn non_blank_count(&self) -> usize {
+        self.pixels.iter().filter(|c| !c.is_blank()).count()
+    }
+
+    /// True if every cell is a space without colours.
+    pub fn is_blank(&self) -> bool {
+        self.pixels.iter().all(TerminalChar::is_blank)
+    }
+
+    /// Smallest rectangle containing every non-blank cell.
+    ///
+    /// ## return
+    /// (x, y, width, height), or `None` if the sprite is blank
+    pub fn bounding_box(&self) -> Option<(u16, u16, u16, u16)> {
+        let mut bounds: Option<(u16, u16, u16, u16)> = None;
+        for (x, y, c) in self.enumerate_cells() {
+            if c.is_blank() {
+                continue;
+            }
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            });
+        }
+        bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+}
+
+/// How long a frame is shown at `fps` frames per second, `None` unless
+/// `fps` is positive and low rates still give a period `Duration` can hold.
+pub(crate) fn frame_period(fps: f32) -> Option<Duration> {
+    if !(fps > 0.0 && fps.is_finite()) {
+        return None;
+    }
+    Duration::try_from_secs_f64(1.0 / fps as f64).ok()
+}
+
+/// Call `progress` after `done` of `total` frames if it is time for an update.
+fn report_progress(progress: &mut dyn FnMut(usize, usize), done: usize, total: usize) {
+    const EVERY: usize = 256;
+    if done.is_multiple_of(EVERY) || done == total {
+        progress(done, total);
+    }
+}
+
+/// Caps on the size of a video accepted by the reader, guarding against
+/// corrupt or malicious headers. Every limit is checked against the header
+/// before any frame is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadLimits {
+    pub max_width: u16,
+    pub max_height: u16,
+    pub max_frames: usize,
+    /// Most memory the decoded frames may take up, counted as
+    /// `frames * width * height * size_of::<TerminalChar>()`.
+    pub max_total_bytes: u64,
+    /// Largest size a gzip compressed file may decompress to. Unlike the
+    /// other limits this doesn't trust the header, which is part of the
+    /// compressed data, so it also bounds a crafted file that inflates to
+    /// far more than its size suggests.
+    pub max_decompressed_bytes: u64,
+}
+
+impl Default for ReadLimits {
+    /// 4096x4096 cells and 100,000 frames, without a memory limit, and
+    /// compressed files inflating to at most 1 GiB.
+    fn default() -> Self {
+        Self {
+            max_width: 4096,
+            max_height: 4096,
+            max_frames: 100_000,
+            max_total_bytes: u64::MAX,
+            max_decompressed_bytes: 1 << 30,
+        }
+    }
+}
+
+/// Number of cells in a `width`x`height` sprite, or an error of `kind` if
+/// that doesn't fit in a `usize` on this platform.
+pub(crate) fn cell_count(width: u16, height: u16, kind: io::ErrorKind) -> io::Result<usize> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .ok_or_else(|| {
+            io::Error::new(
+                kind,
+                format!("{}x{} sprite is too large for this platform", width, height),
+            )
+        })
+}
+
+/// `e` with `path` in front of its message, for errors about one of many files.
+#[cfg(feature = "std")]
+pub(crate) fn with_path(e: io::Error, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+/// Index of cell `(x, y)` in a row-major buffer `width` cells wide, or
+/// `None` if `x` lies outside a row or the index overflows.
+pub(crate) fn index(x: u16, y: u16, width: u16) -> Option<usize> {
+    if x >= width {
+        return None;
+    }
+    (y as usize)
+        .checked_mul(width as usize)?
+        .checked_add(x as usize)
+}
+
+/// Summary of a video file, read from its header alone.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FormatInfo {
+    /// Major format version.
+    pub version: u8,
+    /// Minor format version, 0 for version 1 files.
+    pub minor_version: u8,
+    pub width: u16,
+    pub height: u16,
+    pub frame_count: usize,
+}
+
+/// Identify a video file and summarize it without decoding any frames.
+///
+/// ## Error
+/// if the file isn't an `ASCV` video of a supported version,
+/// or is too short to contain a full header
+#[cfg(feature = "std")]
+pub fn probe(path: impl AsRef<Path>) -> io::Result<FormatInfo> {
+    let f = File::open(path)?;
+    let mut r = BufReader::new(f);
+    match AsciiVideo::read_header(&mut r, ReadLimits::default()) {
+        Ok((info, _)) => Ok(info),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(io::Error::new(
+            e.kind(),
+            "file too short to contain an ASCV header",
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Frame count and frame size of a video, see [`AsciiVideo::size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoDimensions {
+    pub frames: usize,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// A collection of frames that share the same dimensions.
+///
+/// ## File format
+/// ```text
+/// "ASCV"           magic
+/// u8               major version
+/// u16 / u16        width / height
+/// u32              frame count
+/// u8               minor version     (since version 2)
+/// u32 + bytes      header extension  (since version 2)
+/// frames           width * height characters each
+/// ```
+/// The header extension holds tagged sections, currently the
+/// [`AsciiVideo::metadata`] (since version 2.1), the
+/// [`AsciiVideo::char_encoding`] if it isn't the default (since version 2.2),
+/// the playback timing (since version 2.3), the frame labels (since
+/// version 2.4) and a marker for cells storing ANSI-256 palette indices, see
+/// [`Color::ansi256_exact`] (since version 2.5).
+/// All integers are little-endian. A reader accepts every major version
+/// from [`AsciiVideo::MIN_SUPPORTED_VERSION`] to [`AsciiVideo::VERSION`]
+/// and any minor version of those. Additive changes bump only the minor
+/// version and store their fields in the header extension, which older
+/// readers skip, so files stay readable in both directions. The major
+/// version is only bumped for changes old readers can't skip over.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AsciiVideo {
+    pub width: u16,
+    pub height: u16,
+    pub frames: Vec<AsciiSprite>,
+    /// Free-form key/value pairs like author, title or creation time.
+    pub metadata: BTreeMap<String, String>,
+    /// Frames per second, used when no per-frame durations are set.
+    pub frame_rate: Option<f32>,
+    /// How long each frame is shown, either empty or one entry per frame.
+    /// Stored with millisecond precision.
+    pub frame_durations: Vec<Duration>,
+    /// Whether playback restarts after the last frame.
+    pub looping: bool,
+    /// Names of frames, like `"idle"` or `"jump_start"`, either empty or
+    /// one entry per frame. Empty means no frame has a label.
+    pub labels: Vec<Option<String>>,
+    /// Code point encoding used when writing the frames.
+    /// Files written with [`CharEncoding::Varint`] are a lot smaller for
+    /// mostly-ASCII content, but can't be read before format version 2.2.
+    pub char_encoding: CharEncoding,
+}
+
+impl AsciiVideo {
+    /// Magic bytes every video file starts with.
+    pub const MAGIC: [u8; 4] = *b"ASCV";
+    /// Major format version written by this library.
+    pub const VERSION: u8 = 2;
+    /// Minor format version written by this library.
+    pub const MINOR_VERSION: u8 = 5;
+    /// Oldest major format version this library can read.
+    pub const MIN_SUPPORTED_VERSION: u8 = 1;
+    /// Size of the header up to and including the extension length, the
+    /// extension's bytes follow. Version 1 headers end after the frame count,
+    /// 5 bytes earlier.
+    pub const HEADER_SIZE: usize = 18;
+    const MAX_HEADER_EXT: u64 = 1 << 20;
+
+    /// Create a new video
+    ///
+    /// ## Error
+    /// if a dimension is 0 or larger than a reader accepts by default
+    /// (see [`ReadLimits`]), or a frame has another size
+    pub fn new(width: u16, height: u16, frames: Vec<AsciiSprite>) -> io::Result<Self> {
+        Self::check_dimensions(width, height)?;
+        Self::new_unlimited(width, height, frames)
+    }
+
+    /// Reject the dimensions [`AsciiVideo::new`] rejects, for the writers
+    /// that never build a video.
+    pub(crate) fn check_dimensions(width: u16, height: u16) -> io::Result<()> {
+        let limits = ReadLimits::default();
+        if width == 0 || height == 0 || width > limits.max_width || height > limits.max_height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "dimensions {}x{} out of range, max {}x{}",
+                    width, height, limits.max_width, limits.max_height
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// [`AsciiVideo::new`] without the check of the dimensions, for the
+    /// readers, which check them against their own [`ReadLimits`], and for
+    /// videos derived from an existing one.
+    pub(crate) fn new_unlimited(
+        width: u16,
+        height: u16,
+        frames: Vec<AsciiSprite>,
+    ) -> io::Result<Self> {
+        for (i, f) in frames.iter().enumerate() {
+            if f.width != width || f.height != height {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Frame {} has size {}x{} but expected {}x{}",
+                        i, f.width, f.height, width, height
+                    ),
+                ));
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            frames,
+            metadata: BTreeMap::new(),
+            frame_rate: None,
+            frame_durations: Vec::new(),
+            looping: false,
+            labels: Vec::new(),
+            char_encoding: CharEncoding::Fixed,
+        })
+    }
+
+    /// Create a video with the same dimensions and settings as `self`, but
+    /// other frames. The per-frame durations and labels are not carried over.
+    fn with_frames(&self, frames: Vec<AsciiSprite>) -> io::Result<Self> {
+        let mut video = Self::new_unlimited(self.width, self.height, frames)?;
+        video.metadata = self.metadata.clone();
+        video.frame_rate = self.frame_rate;
+        video.looping = self.looping;
+        video.char_encoding = self.char_encoding;
+        Ok(video)
+    }
+
+    /// The settings stored in the header extension.
+    fn extensions(&self) -> header::ExtensionsRef<'_> {
+        header::ExtensionsRef {
+            metadata: &self.metadata,
+            char_encoding: self.char_encoding,
+            frame_rate: self.frame_rate,
+            looping: self.looping,
+            frame_durations: &self.frame_durations,
+            labels: &self.labels,
+            indexed_colors: self.frames.iter().any(|f| {
+                f.pixels
+                    .iter()
+                    .flat_map(|p| [p.fg_color, p.bg_color])
+                    .any(|col| col.is_some_and(|col| col.ansi256().is_some()))
+            }),
+        }
+    }
+
+    /// Apply the settings read from a header extension.
+    pub(crate) fn set_extensions(&mut self, ext: header::Extensions) {
+        self.metadata = ext.metadata;
+        self.char_encoding = ext.char_encoding;
+        self.frame_rate = ext.frame_rate;
+        self.looping = ext.looping;
+        self.frame_durations = ext.frame_durations;
+        self.labels = ext.labels;
+    }
+
+    /// How long frame `index` is shown: its own duration if per-frame
+    /// durations are set, otherwise one frame at the frame rate.
+    /// `None` if the video has no timing, its `frame_rate` isn't a positive
+    /// number with a period that fits a `Duration`, or `index` is out of range.
+    pub fn frame_duration(&self, index: usize) -> Option<Duration> {
+        if index >= self.frames.len() {
+            return None;
+        }
+        if !self.frame_durations.is_empty() {
+            return self.frame_durations.get(index).copied();
+        }
+        self.frame_rate.and_then(frame_period)
+    }
+
+    /// Total playback time, `None` if the video has no timing.
+    pub fn duration(&self) -> Option<Duration> {
+        (0..self.frames.len()).map(|i| self.frame_duration(i)).sum()
+    }
+
+    /// Frame shown at time `t` after the start of playback.
+    ///
+    /// Looping videos wrap around, otherwise `t` past the end gives `None`.
+    /// Also `None` if the video has no frames or no timing, see
+    /// [`AsciiVideo::frame_duration`].
+    pub fn frame_at(&self, t: Duration) -> Option<&AsciiSprite> {
+        let total = self.duration()?;
+        let t = if t < total {
+            t
+        } else if self.looping && !total.is_zero() {
+            let nanos = t.as_nanos() % total.as_nanos();
+            Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            )
+        } else {
+            return None;
+        };
+
+        let mut end = Duration::ZERO;
+        for (i, frame) in self.frames.iter().enumerate() {
+            end += self.frame_duration(i)?;
+            if t < end {
+                return Some(frame);
+            }
+        }
+        self.frames.last()
+    }
+
+    /// Look up a metadata entry.
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Set a metadata entry, returning the previous value if there was one.
+    pub fn set_metadata(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.metadata.insert(key.into(), value.into())
+    }
+
+    /// The label of frame `index`, `None` if it has none or is out of range.
+    pub fn label(&self, index: usize) -> Option<&str> {
+        self.labels.get(index)?.as_deref()
+    }
+
+    /// Label frame `index`, returning its previous label if there was one.
+    ///
+    /// ## Error
+    /// if `index` is out of range
+    pub fn set_label(
+        &mut self,
+        index: usize,
+        name: impl Into<String>,
+    ) -> io::Result<Option<String>> {
+        if index >= self.frames.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame {} out of bounds for video with {} frames",
+                    index,
+                    self.frames.len()
+                ),
+            ));
+        }
+        self.labels.resize(self.frames.len(), None);
+        Ok(self.labels[index].replace(name.into()))
+    }
+
+    /// Remove the label of frame `index`, returning it if there was one.
+    pub fn remove_label(&mut self, index: usize) -> Option<String> {
+        self.labels.get_mut(index)?.take()
+    }
+
+    /// Index of the first frame labelled `name`.
+    pub fn frame_by_label(&self, name: &str) -> Option<usize> {
+        self.labels.iter().position(|l| l.as_deref() == Some(name))
+    }
+
+    /// Return the number of frames and the dimensions.
+    ///
+    /// This used to return a `(frame_count, height, width)` tuple,
+    /// `let (f, h, w) = video.size()` becomes
+    /// `let VideoDimensions { frames: f, height: h, width: w } = video.size()`.
+    pub fn size(&self) -> VideoDimensions {
+        VideoDimensions {
+            frames: self.frames.len(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Total number of cells over all frames, saturating at `usize::MAX`.
+    pub fn cell_count(&self) -> usize {
+        (self.width as usize)
+            .checked_mul(self.height as usize)
+            .and_then(|cells| cells.checked_mul(self.frames.len()))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Upper bound of the number of bytes [`AsciiVideo::write_to`] writes:
+    /// the header with its extension, plus [`TerminalChar::SERIALIZED_SIZE`]
+    /// per cell. It is exact for fixed encoded frames with both colours set
+    /// on every cell, otherwise the file is smaller. Saturates at
+    /// `usize::MAX`.
+    pub fn estimated_file_size(&self) -> usize {
+        let ext = self.extensions().encode().map_or(0, |ext| ext.len());
+        self.cell_count()
+            .checked_mul(TerminalChar::SERIALIZED_SIZE)
+            .and_then(|frames| frames.checked_add(Self::HEADER_SIZE + ext))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Where frame `index` lies in the bytes [`AsciiVideo::write_to`]
+    /// writes. Use it for seeking, memory mapping or HTTP range requests
+    /// into an uncompressed file.
+    ///
+    /// Cells take more or less space depending on their colours and the
+    /// [`AsciiVideo::char_encoding`], so this sums the sizes of all frames
+    /// before; call [`AsciiVideo::frame_offsets`] once to look up many frames.
+    ///
+    /// ## Error
+    /// if `index` is out of bounds, or the header can't be written, see
+    /// [`AsciiVideo::write_to`]
+    pub fn frame_byte_range(&self, index: usize) -> io::Result<Range<u64>> {
+        let Some(start) = self.frame_starts()?.nth(index) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame {} out of bounds for video with {} frames",
+                    index,
+                    self.frames.len()
+                ),
+            ));
+        };
+        Ok(start..start + self.frame_len(&self.frames[index]))
+    }
+
+    /// Byte offset of every frame in the output of [`AsciiVideo::write_to`],
+    /// see [`AsciiVideo::frame_byte_range`].
+    ///
+    /// ## Error
+    /// if the header can't be written, see [`AsciiVideo::write_to`]
+    pub fn frame_offsets(&self) -> io::Result<Vec<u64>> {
+        Ok(self.frame_starts()?.collect())
+    }
+
+    /// The offsets of [`AsciiVideo::frame_offsets`], computed lazily.
+    fn frame_starts(&self) -> io::Result<impl Iterator<Item = u64> + '_> {
+        let mut pos = (Self::HEADER_SIZE + self.extensions().encode()?.len()) as u64;
+        Ok(self.frames.iter().map(move |f| {
+            let start = pos;
+            pos += self.frame_len(f);
+            start
+        }))
+    }
+
+    /// Bytes [`AsciiVideo::write_to`] writes for `frame`.
+    fn frame_len(&self, frame: &AsciiSprite) -> u64 {
+        frame
+            .pixels
+            .iter()
+            .map(|p| p.encoded_size(self.char_encoding, true) as u64)
+            .sum()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to_file_with_progress(path, |_, _| {})
+    }
+
+    /// Like [`AsciiVideo::write_to_file`], calling `progress` with the
+    /// number of frames written so far and the total while writing: once
+    /// before the first frame, every 256 frames and after the last one.
+    #[cfg(feature = "std")]
+    pub fn write_to_file_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(usize, usize),
+    ) -> io::Result<()> {
+        let f = File::create(path)?;
+        let mut w = BufWriter::new(f);
+        self.write_with_progress(&mut w, &mut progress)?;
+        w.flush()
+    }
+
+    /// Read a video file, transparently decompressing gzip compressed files
+    /// like those of `AsciiVideo::write_to_file_gz` (`gzip` feature).
+    #[cfg(feature = "std")]
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from_file_with_limits(path, ReadLimits::default())
+    }
+
+    /// Like [`AsciiVideo::read_from_file`], rejecting videos that exceed `limits`.
+    #[cfg(feature = "std")]
+    pub fn read_from_file_with_limits(
+        path: impl AsRef<Path>,
+        limits: ReadLimits,
+    ) -> io::Result<Self> {
+        Self::read_file(path, limits, &mut |_, _| {})
+    }
+
+    /// Like [`AsciiVideo::read_from_file`], calling `progress` with the
+    /// number of frames read so far and the total while reading: once
+    /// before the first frame, every 256 frames and after the last one.
+    /// Gzip compressed files are decompressed in full before the first call.
+    #[cfg(feature = "std")]
+    pub fn read_from_file_with_progress(
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(usize, usize),
+    ) -> io::Result<Self> {
+        Self::read_file(path, ReadLimits::default(), &mut progress)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_file(
+        path: impl AsRef<Path>,
+        limits: ReadLimits,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> io::Result<Self> {
+        let f = File::open(path)?;
+        let len = f.metadata()?.len();
+        let mut r = BufReader::new(f);
+        if r.fill_buf()?.starts_with(&deflate::GZIP_MAGIC) {
+            let mut data = Vec::new();
+            r.read_to_end(&mut data)?;
+            let raw = Self::decompress_gz(&data, limits)?;
+            let available = Some(raw.len() as u64);
+            return Self::read_sized(&mut raw.as_slice(), available, limits, progress);
+        }
+        Self::read_sized(&mut r, Some(len), limits, progress)
+    }
+
+    /// Read a video file that may have been cut short, e.g. by a crashed
+    /// recording, keeping every complete frame.
+    ///
+    /// The frame count in the header is ignored in favour of the frames
+    /// actually present, and per-frame durations are cut to match. The
+    /// returned flag is `true` if the file ended before the header said it
+    /// would. Gzip compressed files are read in full as by
+    /// [`AsciiVideo::read_from_file`], their checksum doesn't survive truncation.
+    ///
+    /// ## Error
+    /// if the header itself is incomplete or invalid, or a frame holds invalid data
+    #[cfg(feature = "std")]
+    pub fn read_from_file_lenient(path: impl AsRef<Path>) -> io::Result<(Self, bool)> {
+        let f = File::open(path)?;
+        let mut r = BufReader::new(f);
+        if r.fill_buf()?.starts_with(&deflate::GZIP_MAGIC) {
+            let mut data = Vec::new();
+            r.read_to_end(&mut data)?;
+            let raw = Self::decompress_gz(&data, ReadLimits::default())?;
+            return Self::read_lenient(&mut raw.as_slice());
+        }
+        Self::read_lenient(&mut r)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_lenient<R: Read>(r: &mut R) -> io::Result<(Self, bool)> {
+        let (info, mut ext) = Self::read_header(r, ReadLimits::default())?;
+        let mut frames = Vec::new();
+        for _ in 0..info.frame_count {
+            match AsciiSprite::read_encoded(r, info.width, info.height, ext.char_encoding) {
+                Ok(frame) => frames.push(frame),
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+        }
+        let truncated = frames.len() < info.frame_count;
+        if !ext.frame_durations.is_empty() {
+            ext.frame_durations.truncate(frames.len());
+        }
+        if !ext.labels.is_empty() {
+            ext.labels.truncate(frames.len());
+        }
+
+        let mut video = Self::new_unlimited(info.width, info.height, frames)?;
+        video.set_extensions(ext);
+        Ok((video, truncated))
+    }
+
+    /// Read only the first frame of a video file, e.g. for a preview.
+    ///
+    /// Reading stops right after that frame. For gzip compressed files,
+    /// only as much is decompressed as the header and the first frame can
+    /// take up, the checksum of the whole file isn't verified.
+    ///
+    /// ## Error
+    /// if the video has no frames, or see [`AsciiVideo::read_from_file`]
+    #[cfg(feature = "std")]
+    pub fn read_thumbnail(path: impl AsRef<Path>) -> io::Result<AsciiSprite> {
+        let f = File::open(path)?;
+        let mut r = BufReader::new(f);
+        if r.fill_buf()?.starts_with(&deflate::GZIP_MAGIC) {
+            let mut data = Vec::new();
+            r.read_to_end(&mut data)?;
+            let raw = Self::decompress_gz_prefix(&data, ReadLimits::default(), 1)?;
+            return Self::read_first_frame(&mut raw.as_slice());
+        }
+        Self::read_first_frame(&mut r)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_first_frame<R: Read>(r: &mut R) -> io::Result<AsciiSprite> {
+        let (info, ext) = Self::read_header(r, ReadLimits::default())?;
+        if info.frame_count == 0 {
+            return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
-                "dimensions out of range, max 4096x4096",
+                "video has no frames",
+            ));
+        }
+        AsciiSprite::read_encoded(r, info.width, info.height, ext.char_encoding)
+    }
+
+    /// Write the video as a gzip compressed file.
+    ///
+    /// Recordings are highly repetitive: 300 frames of scrolling, coloured
+    /// 80x24 text shrink about 15x, mostly static content far more.
+    /// The output is plain gzip, so `gunzip` restores the uncompressed file.
+    /// The whole file is assembled in memory before compression.
+    #[cfg(feature = "gzip")]
+    pub fn write_to_file_gz(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut raw = Vec::new();
+        self.write_to(&mut raw)?;
+        std::fs::write(path, gzip::gzip(&raw))
+    }
+
+    /// Read a gzip compressed video file.
+    ///
+    /// ## Error
+    /// if the file isn't gzip compressed, see [`AsciiVideo::read_from_file`]
+    /// for reading either kind
+    #[cfg(feature = "gzip")]
+    pub fn read_from_file_gz(path: impl AsRef<Path>) -> io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let raw = Self::decompress_gz(&data, ReadLimits::default())?;
+        let available = Some(raw.len() as u64);
+        Self::read_sized(
+            &mut raw.as_slice(),
+            available,
+            ReadLimits::default(),
+            &mut |_, _| {},
+        )
+    }
+
+    /// Decompress a gzip compressed video file, checking its header against
+    /// `limits` first. Inflating stops with `InvalidData` once the output
+    /// grows beyond what the declared frames can take up, or beyond
+    /// [`ReadLimits::max_decompressed_bytes`].
+    #[cfg(feature = "gzip")]
+    pub(crate) fn decompress_gz(data: &[u8], limits: ReadLimits) -> io::Result<Vec<u8>> {
+        let (header_len, info, _) = Self::gzip_header(data, limits)?;
+        let frames_len = Self::max_frames_len(&info, info.frame_count);
+        let len = header_len.saturating_add(frames_len);
+        gzip::gunzip(data, len.min(Self::max_inflated(limits)))
+    }
+
+    /// Decompress only the header and the first `frames` frames of a gzip
+    /// compressed video file, without verifying the checksum.
+    #[cfg(feature = "gzip")]
+    fn decompress_gz_prefix(data: &[u8], limits: ReadLimits, frames: usize) -> io::Result<Vec<u8>> {
+        let (header_len, info, _) = Self::gzip_header(data, limits)?;
+        let len = header_len.saturating_add(Self::max_frames_len(&info, frames));
+        gzip::gunzip_prefix(data, len.min(Self::max_inflated(limits)))
+    }
+
+    /// Without the `gzip` feature compressed files are recognised, but
+    /// can't be read.
+    #[cfg(all(feature = "std", not(feature = "gzip")))]
+    pub(crate) fn decompress_gz(_: &[u8], _: ReadLimits) -> io::Result<Vec<u8>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "gzip compressed file, but the `gzip` feature is disabled",
+        ))
+    }
+
+    #[cfg(all(feature = "std", not(feature = "gzip")))]
+    fn decompress_gz_prefix(data: &[u8], limits: ReadLimits, _: usize) -> io::Result<Vec<u8>> {
+        Self::decompress_gz(data, limits)
+    }
+
+    /// [`ReadLimits::max_decompressed_bytes`] as a buffer length.
+    #[cfg(feature = "gzip")]
+    fn max_inflated(limits: ReadLimits) -> usize {
+        usize::try_from(limits.max_decompressed_bytes).unwrap_or(usize::MAX)
+    }
+
+    /// Read the header at the start of a gzip compressed video file,
+    /// returning its length with what it holds.
+    #[cfg(feature = "gzip")]
+    fn gzip_header(
+        data: &[u8],
+        limits: ReadLimits,
+    ) -> io::Result<(usize, FormatInfo, header::Extensions)> {
+        let max_len = Self::HEADER_SIZE + Self::MAX_HEADER_EXT as usize;
+        let head = gzip::gunzip_prefix(data, max_len.min(Self::max_inflated(limits)))?;
+        let mut r = head.as_slice();
+        let (info, ext) = Self::read_header(&mut r, limits)?;
+        Ok((head.len() - r.len(), info, ext))
+    }
+
+    /// Most bytes `frames` frames of the size in `info` can take up.
+    #[cfg(feature = "gzip")]
+    fn max_frames_len(info: &FormatInfo, frames: usize) -> usize {
+        (info.width as usize)
+            .saturating_mul(info.height as usize)
+            .saturating_mul(frames)
+            .saturating_mul(TerminalChar::SERIALIZED_SIZE)
+    }
+
+    /// Serialise the video, header first, then every frame
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_with_progress(w, &mut |_, _| {})
+    }
+
+    fn write_with_progress<W: Write>(
+        &self,
+        w: &mut W,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> io::Result<()> {
+        if let Some(fps) = self.frame_rate.filter(|&fps| frame_period(fps).is_none()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid frame rate {}", fps),
+            ));
+        }
+        if !self.frame_durations.is_empty() && self.frame_durations.len() != self.frames.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} frame durations for {} frames",
+                    self.frame_durations.len(),
+                    self.frames.len()
+                ),
+            ));
+        }
+        if !self.labels.is_empty() && self.labels.len() != self.frames.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} frame labels for {} frames",
+                    self.labels.len(),
+                    self.frames.len()
+                ),
+            ));
+        }
+        let ext = self.extensions().encode()?;
+        if ext.len() as u64 > Self::MAX_HEADER_EXT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "header extension too large: {} bytes (max {})",
+                    ext.len(),
+                    Self::MAX_HEADER_EXT
+                ),
+            ));
+        }
+
+        Self::write_header(w, self.width, self.height, self.frames.len() as u32, &ext)?;
+
+        // Frames
+        let total = self.frames.len();
+        progress(0, total);
+        for (i, f) in self.frames.iter().enumerate() {
+            f.write_encoded(w, self.char_encoding, true)?;
+            report_progress(progress, i + 1, total);
+        }
+        Ok(())
+    }
+
+    /// Byte offset of the `u32` frame count in the header.
+    #[cfg(feature = "std")]
+    pub(crate) const FRAME_COUNT_OFFSET: u64 = 9;
+
+    pub(crate) fn write_header<W: Write>(
+        w: &mut W,
+        width: u16,
+        height: u16,
+        frame_count: u32,
+        ext: &[u8],
+    ) -> io::Result<()> {
+        w.write_all(&Self::MAGIC)?;
+        w.write_u8(Self::VERSION)?;
+        w.write_u16::<LittleEndian>(width)?;
+        w.write_u16::<LittleEndian>(height)?;
+        w.write_u32::<LittleEndian>(frame_count)?;
+        w.write_u8(Self::MINOR_VERSION)?;
+        w.write_u32::<LittleEndian>(ext.len() as u32)?;
+        w.write_all(ext)
+    }
+
+    /// Deserialise a video written by [`AsciiVideo::write_to`]
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        Self::read_from_with_limits(r, ReadLimits::default())
+    }
+
+    /// Like [`AsciiVideo::read_from`], rejecting videos that exceed `limits`.
+    pub fn read_from_with_limits<R: Read>(r: &mut R, limits: ReadLimits) -> io::Result<Self> {
+        Self::read_sized(r, None, limits, &mut |_, _| {})
+    }
+
+    /// Read a video, first checking the header against the `available`
+    /// number of bytes if it is known.
+    fn read_sized<R: Read>(
+        r: &mut R,
+        available: Option<u64>,
+        limits: ReadLimits,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> io::Result<Self> {
+        let (info, ext) = Self::read_header(r, limits)?;
+        if let Some(available) = available {
+            Self::check_declared_size(&info, ext.char_encoding, available)?;
+        }
+
+        // frames
+        let mut frames = Vec::with_capacity(info.frame_count);
+        progress(0, info.frame_count);
+        for i in 0..info.frame_count {
+            frames.push(AsciiSprite::read_encoded(
+                r,
+                info.width,
+                info.height,
+                ext.char_encoding,
+            )?);
+            report_progress(progress, i + 1, info.frame_count);
+        }
+
+        let mut video = Self::new_unlimited(info.width, info.height, frames)?;
+        video.set_extensions(ext);
+        Ok(video)
+    }
+
+    /// Serialise the video into a buffer, exactly as [`AsciiVideo::write_to`] would.
+    ///
+    /// Useful with async runtimes: encode here, then hand the bytes to the
+    /// runtime's own writer instead of blocking the executor on a `File`.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Deserialise a video from a buffer, e.g. one filled by an async
+    /// `read_to_end`. Validation is the same as in [`AsciiVideo::read_from`].
+    /// [`AsciiVideoDecoder`] decodes the bytes as they arrive instead.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        Self::read_sized(
+            &mut &bytes[..],
+            Some(bytes.len() as u64),
+            ReadLimits::default(),
+            &mut |_, _| {},
+        )
+    }
+
+    /// Reject a header declaring more frames than `available` bytes can
+    /// hold, before anything is allocated for them.
+    pub(crate) fn check_declared_size(
+        info: &FormatInfo,
+        encoding: CharEncoding,
+        available: u64,
+    ) -> io::Result<()> {
+        let min_cell = TerminalChar::default().serialized_size(encoding) as u64;
+        let needed = (info.frame_count as u64)
+            .checked_mul(info.width as u64 * info.height as u64)
+            .and_then(|cells| cells.checked_mul(min_cell));
+        match needed {
+            Some(needed) if needed <= available => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "header declares {} frames of {}x{}, more than {} bytes can hold",
+                    info.frame_count, info.width, info.height, available
+                ),
+            )),
+        }
+    }
+
+    /// Read and validate the file header, leaving `r` at the first frame.
+    pub(crate) fn read_header<R: Read>(
+        r: &mut R,
+        limits: ReadLimits,
+    ) -> io::Result<(FormatInfo, header::Extensions)> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if magic != Self::MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "bad magic number",
+            ));
+        }
+
+        let ver = r.read_u8()?;
+        if !(Self::MIN_SUPPORTED_VERSION..=Self::VERSION).contains(&ver) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported version {} (supported {}..={})",
+                    ver,
+                    Self::MIN_SUPPORTED_VERSION,
+                    Self::VERSION
+                ),
+            ));
+        }
+
+        let width = r.read_u16::<LittleEndian>()?;
+        let height = r.read_u16::<LittleEndian>()?;
+        let frame_count = r.read_u32::<LittleEndian>()? as usize;
+
+        if width == 0 || height == 0 || width > limits.max_width || height > limits.max_height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "dimensions {}x{} out of range, max {}x{}",
+                    width, height, limits.max_width, limits.max_height
+                ),
+            ));
+        }
+
+        if frame_count > limits.max_frames {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "too many frames: {} (max {})",
+                    frame_count, limits.max_frames
+                ),
+            ));
+        }
+
+        let total_bytes = (frame_count as u64)
+            .saturating_mul(width as u64 * height as u64)
+            .saturating_mul(size_of::<TerminalChar>() as u64);
+        if total_bytes > limits.max_total_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "decoded frames would take {} bytes (max {})",
+                    total_bytes, limits.max_total_bytes
+                ),
+            ));
+        }
+
+        let mut minor_version = 0;
+        let mut ext = header::Extensions::default();
+        if ver >= 2 {
+            // Any minor version is readable, sections added by newer minors
+            // are skipped when parsing the extension block.
+            minor_version = r.read_u8()?;
+            let ext_len = r.read_u32::<LittleEndian>()? as u64;
+            if ext_len > Self::MAX_HEADER_EXT {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "header extension too large: {} bytes (max {})",
+                        ext_len,
+                        Self::MAX_HEADER_EXT
+                    ),
+                ));
+            }
+            let mut block = vec![0; ext_len as usize];
+            r.read_exact(&mut block)?;
+            ext = header::Extensions::decode(&block)?;
+            if !ext.frame_durations.is_empty() && ext.frame_durations.len() != frame_count {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} frame durations for {} frames",
+                        ext.frame_durations.len(),
+                        frame_count
+                    ),
+                ));
+            }
+            if !ext.labels.is_empty() && ext.labels.len() != frame_count {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} frame labels for {} frames",
+                        ext.labels.len(),
+                        frame_count
+                    ),
+                ));
+            }
+        }
+
+        let info = FormatInfo {
+            version: ver,
+            minor_version,
+            width,
+            height,
+            frame_count,
+        };
+        Ok((info, ext))
+    }
+
+    /// Return a single frame as a two-dimensional grid.
+    pub fn get_frame(&self, index: usize) -> Option<Vec<Vec<TerminalChar>>> {
+        self.frames.get(index).map(|s| s.as_grid())
+    }
+
+    /// Return a single frame as a flat vector.
+    ///
+    /// This copies the whole pixel buffer on every call, use
+    /// [`AsciiVideo::frame_pixels`] to read a frame without allocating.
+    pub fn get_frame_flat(&self, index: usize) -> Option<Vec<TerminalChar>> {
+        Some(self.frames.get(index)?.as_flat())
+    }
+
+    /// Borrow the pixels of a single frame, row by row.
+    pub fn frame_pixels(&self, index: usize) -> Option<&[TerminalChar]> {
+        self.frames.get(index).map(|s| s.pixels.as_slice())
+    }
+
+    /// The frames in playback order.
+    pub fn frames(&self) -> &[AsciiSprite] {
+        &self.frames
+    }
+
+    /// The frames in playback order, to be edited in place.
+    /// Frames should keep the video's dimensions.
+    pub fn frames_mut(&mut self) -> &mut [AsciiSprite] {
+        &mut self.frames
+    }
+
+    /// Iterate over the frames in playback order.
+    pub fn iter(&self) -> core::slice::Iter<'_, AsciiSprite> {
+        self.frames.iter()
+    }
+
+    /// Like [`AsciiVideo::iter`], but the frames can be modified.
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, AsciiSprite> {
+        self.frames.iter_mut()
+    }
+
+    /// Append the frames of `other` to the end of this video.
+    ///
+    /// If the videos are timed differently, every frame keeps its duration
+    /// through per-frame durations. Frames of a video without timing last
+    /// one period of the other video's frame rate.
+    ///
+    /// ## Error
+    /// if `other` doesn't have the same dimensions as `self`, or one video
+    /// has no timing and the other no frame rate to take it from
+    pub fn concat(&mut self, other: &AsciiVideo) -> io::Result<()> {
+        if other.width != self.width || other.height != self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot append a {}x{} video to a {}x{} video",
+                    other.width, other.height, self.width, self.height
+                ),
+            ));
+        }
+        let same_timing = self.frame_durations.is_empty()
+            && other.frame_durations.is_empty()
+            && self.frame_rate == other.frame_rate;
+        if !same_timing {
+            let own_period = self.frame_rate.and_then(frame_period);
+            let other_period = other.frame_rate.and_then(frame_period);
+            let durations: Option<Vec<_>> = (0..self.frames.len())
+                .map(|i| self.frame_duration(i).or(other_period))
+                .chain((0..other.frames.len()).map(|i| other.frame_duration(i).or(own_period)))
+                .collect();
+            self.frame_durations = durations.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "cannot append videos when one has no timing and the other no frame rate",
+                )
+            })?;
+            self.frame_rate = self.frame_rate.or(other.frame_rate);
+        }
+        if !self.labels.is_empty() || !other.labels.is_empty() {
+            self.labels.resize(self.frames.len(), None);
+            self.labels
+                .extend((0..other.frames.len()).map(|i| other.label(i).map(String::from)));
+        }
+        self.frames.extend_from_slice(&other.frames);
+        Ok(())
+    }
+
+    /// Return a new video containing the frames in `start..end`.
+    ///
+    /// ## Error
+    /// if `start > end` or `end` is past the last frame
+    pub fn slice(&self, start: usize, end: usize) -> io::Result<AsciiVideo> {
+        if start > end || end > self.frames.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame range {}..{} out of bounds for video with {} frames",
+                    start,
+                    end,
+                    self.frames.len()
+                ),
+            ));
+        }
+        let mut video = self.with_frames(self.frames[start..end].to_vec())?;
+        if !self.frame_durations.is_empty() {
+            video.frame_durations = self.frame_durations[start..end].to_vec();
+        }
+        if !self.labels.is_empty() {
+            video.labels = self.labels[start..end].to_vec();
+        }
+        Ok(video)
+    }
+
+    /// Return a new video that only keeps every `keep_every`-th frame,
+    /// starting with frame 0. The timing is adjusted so playback takes as
+    /// long as before.
+    ///
+    /// ## Error
+    /// if `keep_every` is 0
+    pub fn downsample(&self, keep_every: usize) -> io::Result<AsciiVideo> {
+        if keep_every == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "keep_every must be at least 1",
+            ));
+        }
+        let frames = self.frames.iter().step_by(keep_every).cloned().collect();
+        let mut video = self.with_frames(frames)?;
+        video.frame_rate = self.frame_rate.map(|fps| fps / keep_every as f32);
+        video.frame_durations = self
+            .frame_durations
+            .chunks(keep_every)
+            .map(|c| c.iter().sum())
+            .collect();
+        // a kept frame takes the first label of the frames it stands for
+        video.labels = self
+            .labels
+            .chunks(keep_every)
+            .map(|c| c.iter().flatten().next().cloned())
+            .collect();
+        Ok(video)
+    }
+
+    /// Retime the video to a constant `target_fps`, duplicating frames when
+    /// it is higher than the source's rate and dropping them when it is lower.
+    ///
+    /// Every output frame shows the source frame on screen at its midpoint,
+    /// following the frame rate or per-frame durations. Playback takes as
+    /// long as before, rounded to whole output frames.
+    ///
+    /// ## Error
+    /// if `target_fps` isn't a positive number, the video has no timing, or
+    /// the result would have more frames than a reader accepts by default
+    /// (see [`ReadLimits`])
+    pub fn resample_fps(&self, target_fps: f32) -> io::Result<AsciiVideo> {
+        if frame_period(target_fps).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid frame rate {}", target_fps),
+            ));
+        }
+        let total = self.duration().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "video has no frame rate or durations to resample",
+            )
+        })?;
+        let fps = target_fps as f64;
+        let count = total.as_secs_f64() * fps + 0.5;
+        let max_frames = ReadLimits::default().max_frames;
+        if count >= max_frames as f64 + 1.0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "resampling to {} fps gives more than {} frames",
+                    target_fps, max_frames
+                ),
             ));
         }
+        let last = total.saturating_sub(Duration::from_nanos(1));
+        let frames = (0..count as usize)
+            .map(|k| {
+                let t = Duration::try_from_secs_f64((k as f64 + 0.5) / fps)
+                    .map_or(last, |t| t.min(last));
+                self.frame_at(t).expect("t lies within the video").clone()
+            })
+            .collect();
+
+        let mut video = self.with_frames(frames)?;
+        video.frame_rate = Some(target_fps);
+        Ok(video)
+    }
 
-        if frame_count > 100_000 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("too many frames: {} (max {})", frame_count, 100_000),
-            ));
+    /// Collapse runs of identical consecutive frames into one, returning the
+    /// new video and how many frames of the original every kept frame stands for.
+    ///
+    /// If the video is timed, each kept frame gets the summed duration of
+    /// its run in [`AsciiVideo::frame_durations`], so playback is unchanged.
+    /// It also takes the first label within its run.
+    pub fn dedup_consecutive(&self) -> (AsciiVideo, Vec<u32>) {
+        let timed = self.duration().is_some();
+        let mut frames: Vec<AsciiSprite> = Vec::new();
+        let mut counts: Vec<u32> = Vec::new();
+        let mut durations = Vec::new();
+        let mut labels: Vec<Option<String>> = Vec::new();
+        for (i, frame) in self.frames.iter().enumerate() {
+            let duration = self.frame_duration(i).unwrap_or_default();
+            let label = self.label(i).map(String::from);
+            if frames.last() == Some(frame) {
+                let last = counts.len() - 1;
+                counts[last] = counts[last].saturating_add(1);
+                durations[last] += duration;
+                if labels[last].is_none() {
+                    labels[last] = label;
+                }
+            } else {
+                frames.push(frame.clone());
+                counts.push(1);
+                durations.push(duration);
+                labels.push(label);
+            }
         }
 
-        // frames
-        let mut frames = Vec::with_capacity(frame_count);
-        for _ in 0..frame_count {
-            frames.push(AsciiSprite::read_from(&mut r, width, height)?);
+        let mut video = self
+            .with_frames(frames)
+            .expect("frames come from a valid video");
+        if timed {
+            video.frame_durations = durations;
+        }
+        if !self.labels.is_empty() {
+            video.labels = labels;
         }
+        (video, counts)
+    }
+
+    /// Blend `count` frames starting at `start` into a single ghosted sprite.
+    ///
+    /// Every cell gets the average of the frames' colours (see [`Color::blend`]),
+    /// ignoring frames where the colour is unset, and the character of the
+    /// latest frame that isn't whitespace there.
+    ///
+    /// ## Error
+    /// if `count` is 0 or the range runs past the last frame
+    pub fn blend_frames(&self, start: usize, count: usize) -> io::Result<AsciiSprite> {
+        let end = start
+            .checked_add(count)
+            .filter(|&end| end <= self.frames.len());
+        let frames = match end {
+            Some(end) if count > 0 => &self.frames[start..end],
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "can't blend {} frames from frame {} of a video with {} frames",
+                        count,
+                        start,
+                        self.frames.len()
+                    ),
+                ));
+            }
+        };
 
-        Self::new(width, height, frames)
+        // running mean: the n-th colour is weighted 1/n
+        let average = |acc: Option<(Color, u32)>, col: Option<Color>| match col {
+            Some(c) if !c.reset => Some(match acc {
+                None => (c, 1),
+                Some((a, n)) => (a.blend(c, 1.0 / (n + 1) as f32), n + 1),
+            }),
+            _ => acc,
+        };
+
+        let pixels = (0..frames[0].pixels.len())
+            .map(|i| {
+                let mut chr = ' ';
+                let (mut fg, mut bg) = (None, None);
+                for frame in frames {
+                    let cell = frame.pixels[i];
+                    if !cell.chr.is_whitespace() {
+                        chr = cell.chr;
+                    }
+                    fg = average(fg, cell.fg_color);
+                    bg = average(bg, cell.bg_color);
+                }
+                TerminalChar {
+                    chr,
+                    fg_color: fg.map(|(c, _)| c),
+                    bg_color: bg.map(|(c, _)| c),
+                }
+            })
+            .collect();
+        AsciiSprite::new(self.width, self.height, pixels)
     }
 
-    /// Return a single frame as a two-dimensional grid.
-    pub fn get_frame(&self, index: usize) -> Option<Vec<Vec<TerminalChar>>> {
-        self.frames.get(index).map(|s| s.as_grid())
+    /// For every frame after the first, the number of cells that differ
+    /// from the previous frame, i.e. what a delta encoding would store.
+    pub fn change_stats(&self) -> Vec<usize> {
+        self.frames
+            .windows(2)
+            .map(|pair| {
+                let (prev, next) = (&pair[0].pixels, &pair[1].pixels);
+                prev.iter().zip(next).filter(|(a, b)| a != b).count()
+            })
+            .collect()
     }
 
-    /// Return a single frame as a flat vector.
-    pub fn get_frame_flat(&self, index: usize) -> Option<Vec<TerminalChar>> {
-        Some(self.frames.get(index)?.as_flat())
+    /// Fraction of cells that change between consecutive frames, from 0.0
+    /// (a still image) to 1.0 (every cell changes every frame). The lower it
+    /// is, the more a delta encoding would save. Videos with fewer than two
+    /// frames give 0.0.
+    pub fn compression_estimate(&self) -> f32 {
+        let cells = (self.width as usize * self.height as usize) as f64;
+        let total = cells * self.frames.len().saturating_sub(1) as f64;
+        if total == 0.0 {
+            return 0.0;
+        }
+        let changed: usize = self.change_stats().iter().sum();
+        (changed as f64 / total) as f32
     }
 
     /// Convert all frames to grids.    
@@ -415,10 +2328,44 @@ impl AsciiVideo {
     }
 }
 
+impl Index<usize> for AsciiVideo {
+    type Output = AsciiSprite;
+
+    fn index(&self, index: usize) -> &AsciiSprite {
+        &self.frames[index]
+    }
+}
+
+impl IndexMut<usize> for AsciiVideo {
+    fn index_mut(&mut self, index: usize) -> &mut AsciiSprite {
+        &mut self.frames[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a AsciiVideo {
+    type Item = &'a AsciiSprite;
+    type IntoIter = core::slice::Iter<'a, AsciiSprite>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut AsciiVideo {
+    type Item = &'a mut AsciiSprite;
+    type IntoIter = core::slice::IterMut<'a, AsciiSprite>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::Rng;
+    use alloc::string::ToString;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn test_video_size() {
@@ -434,7 +2381,446 @@ mod tests {
         let sprite2 = AsciiSprite::new(2, 3, pixels).unwrap();
 
         let video = AsciiVideo::new(2, 3, vec![sprite1, sprite2]).unwrap();
-        assert_eq!(video.size(), (2, 3, 2));
+        assert_eq!(
+            video.size(),
+            VideoDimensions {
+                frames: 2,
+                width: 2,
+                height: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_estimated_file_size() {
+        let sprite = AsciiSprite::new(2, 3, vec![TerminalChar::from('x'); 6]).unwrap();
+        let mut video = AsciiVideo::new(2, 3, vec![sprite; 2]).unwrap();
+        assert_eq!(video.cell_count(), 12);
+
+        video.set_metadata("title", "size");
+        let mut buf = Vec::new();
+        video.write_to(&mut buf).unwrap();
+        assert!(buf.len() <= video.estimated_file_size());
+        for frame in &mut video {
+            frame.fill(TerminalChar::with_colors('x', Color::Red, Color::Navy));
+        }
+        buf.clear();
+        video.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), video.estimated_file_size());
+    }
+
+    #[test]
+    fn test_video_frame_access() {
+        let frames = (b'a'..=b'c')
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c as char)]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(1, 1, frames).unwrap();
+
+        assert_eq!(video[1].pixels[0].chr, 'b');
+        video[1].fill(TerminalChar::from('x'));
+        for frame in &mut video {
+            frame.replace_char('a', 'z');
+        }
+        let chars: String = video.iter().map(|f| f.pixels[0].chr).collect();
+        assert_eq!(chars, "zxc");
+        assert_eq!((&video).into_iter().count(), video.frames().len());
+        video.frames_mut().reverse();
+        assert_eq!(video[0].pixels[0].chr, 'c');
+        assert_eq!(video.frame_pixels(2).unwrap(), [TerminalChar::from('z')]);
+        assert_eq!(video.frame_pixels(2), video.get_frame_flat(2).as_deref());
+        assert!(video.frame_pixels(3).is_none());
+    }
+
+    #[test]
+    fn test_video_concat_and_slice() {
+        let frame = |c: char| AsciiSprite::new(2, 1, vec![TerminalChar::from(c); 2]).unwrap();
+        let mut video = AsciiVideo::new(2, 1, vec![frame('a'), frame('b')]).unwrap();
+        let other = AsciiVideo::new(2, 1, vec![frame('c')]).unwrap();
+
+        video.concat(&other).unwrap();
+        assert_eq!(video.frames.len(), 3);
+        assert_eq!(video.frames[2], frame('c'));
+
+        let clip = video.slice(1, 3).unwrap();
+        assert_eq!(clip.frames, vec![frame('b'), frame('c')]);
+        assert!(video.slice(2, 4).is_err());
+        assert!(video.slice(2, 1).is_err());
+
+        let wrong_size = AsciiVideo::new(1, 1, vec![]).unwrap();
+        let err = video.concat(&wrong_size).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_video_downsample() {
+        let frames = ['a', 'b', 'c', 'd', 'e']
+            .into_iter()
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c)]).unwrap())
+            .collect();
+        let video = AsciiVideo::new(1, 1, frames).unwrap();
+
+        let preview = video.downsample(2).unwrap();
+        let chars: Vec<char> = preview.frames.iter().map(|f| f.pixels[0].chr).collect();
+        assert_eq!(chars, vec!['a', 'c', 'e']);
+        assert_eq!(video.downsample(1).unwrap(), video);
+        assert!(video.downsample(0).is_err());
+    }
+
+    #[test]
+    fn test_video_blend_frames() {
+        let frames = [
+            TerminalChar::with_fg('a', Color::rgb(0, 0, 0)),
+            TerminalChar::with_colors(' ', Color::rgb(90, 30, 0), Color::White),
+            TerminalChar::with_fg('c', Color::rgb(30, 60, 255)),
+        ]
+        .into_iter()
+        .map(|c| AsciiSprite::new(1, 1, vec![c]).unwrap())
+        .collect();
+        let video = AsciiVideo::new(1, 1, frames).unwrap();
+
+        let ghost = video.blend_frames(0, 3).unwrap().pixels[0];
+        assert_eq!(ghost.chr, 'c');
+        assert_eq!(ghost.fg_color, Some(Color::rgb(40, 30, 85)));
+        assert_eq!(ghost.bg_color, Some(Color::White));
+        assert_eq!(video.blend_frames(1, 1).unwrap().pixels[0].chr, ' ');
+        assert!(video.blend_frames(2, 2).is_err());
+        assert!(video.blend_frames(0, 0).is_err());
+        assert!(video.blend_frames(usize::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn test_video_change_stats() {
+        let frame = |s: &str| AsciiSprite::from_rows(vec![s.into()]).unwrap();
+        let video = AsciiVideo::new(
+            4,
+            1,
+            vec![frame("abcd"), frame("abcd"), frame("xbcy"), frame("wxyz")],
+        )
+        .unwrap();
+        assert_eq!(video.change_stats(), [0, 2, 4]);
+        assert_eq!(video.compression_estimate(), 0.5);
+
+        let still = AsciiVideo::new(4, 1, vec![frame("abcd")]).unwrap();
+        assert!(still.change_stats().is_empty());
+        assert_eq!(still.compression_estimate(), 0.0);
+    }
+
+    #[test]
+    fn test_video_frame_at() {
+        let frames = ['a', 'b', 'c']
+            .into_iter()
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c)]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(1, 1, frames).unwrap();
+        let chr_at = |v: &AsciiVideo, ms| {
+            v.frame_at(Duration::from_millis(ms))
+                .map(|f| f.pixels[0].chr)
+        };
+        assert_eq!(chr_at(&video, 0), None);
+
+        video.frame_rate = Some(2.0);
+        assert_eq!(video.duration(), Some(Duration::from_millis(1500)));
+        assert_eq!(chr_at(&video, 0), Some('a'));
+        assert_eq!(chr_at(&video, 1200), Some('c'));
+        assert_eq!(chr_at(&video, 1500), None);
+        video.looping = true;
+        assert_eq!(chr_at(&video, 3200), Some('a'));
+
+        video.frame_durations = [100, 2000, 400].map(Duration::from_millis).to_vec();
+        assert_eq!(chr_at(&video, 2099), Some('b'));
+        assert_eq!(chr_at(&video, 2100 + 2500), Some('c'));
+
+        let back = AsciiVideo::from_bytes(&video.to_bytes().unwrap()).unwrap();
+        assert_eq!(back, video);
+        video.frame_durations.pop();
+        assert!(video.to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_unusable_frame_rate() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('x')]).unwrap();
+        let mut video = AsciiVideo::new(1, 1, vec![frame; 2]).unwrap();
+        for fps in [0.0, -1.0, f32::NAN, 1e-30] {
+            video.frame_rate = Some(fps);
+            assert_eq!(video.frame_duration(0), None);
+            assert_eq!(video.duration(), None);
+            assert!(video.frame_at(Duration::ZERO).is_none());
+            assert!(video.resample_fps(10.0).is_err());
+            assert_eq!(
+                video.to_bytes().unwrap_err().kind(),
+                io::ErrorKind::InvalidInput
+            );
+        }
+        assert!(video.resample_fps(1e-30).is_err());
+
+        // a file declaring such a rate is rejected instead of crashing later
+        video.frame_rate = Some(2.0);
+        let mut bytes = video.to_bytes().unwrap();
+        let pos = bytes
+            .windows(4)
+            .position(|w| w == 2.0f32.to_le_bytes())
+            .unwrap();
+        bytes[pos..pos + 4].copy_from_slice(&1e-30f32.to_le_bytes());
+        let err = AsciiVideo::from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_video_timing_transforms() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('x')]).unwrap();
+        let mut video = AsciiVideo::new(1, 1, vec![frame; 4]).unwrap();
+        video.frame_rate = Some(10.0);
+        assert_eq!(video.downsample(2).unwrap().frame_rate, Some(5.0));
+
+        let mut slow = video.slice(0, 2).unwrap();
+        slow.frame_rate = Some(1.0);
+        video.concat(&slow).unwrap();
+        assert_eq!(video.duration(), Some(Duration::from_millis(2400)));
+        assert_eq!(
+            video.slice(3, 5).unwrap().duration(),
+            Some(Duration::from_millis(1100))
+        );
+
+        let halved = video.downsample(2).unwrap();
+        assert_eq!(halved.frame_durations.len(), 3);
+        assert_eq!(halved.duration(), video.duration());
+    }
+
+    #[test]
+    fn test_video_concat_timed_untimed() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('x')]).unwrap();
+        let untimed = AsciiVideo::new(1, 1, vec![frame.clone(); 2]).unwrap();
+        let mut timed = AsciiVideo::new(1, 1, vec![frame; 3]).unwrap();
+        timed.frame_rate = Some(10.0);
+
+        // the untimed frames take the period of the timed video
+        let mut video = timed.clone();
+        video.concat(&untimed).unwrap();
+        assert_eq!(video.duration(), Some(Duration::from_millis(500)));
+        let mut video = untimed.clone();
+        video.concat(&timed).unwrap();
+        assert_eq!(video.duration(), Some(Duration::from_millis(500)));
+        assert_eq!(video.frame_rate, Some(10.0));
+
+        // per-frame durations alone give nothing to fill in from
+        let mut durations_only = timed.clone();
+        durations_only.frame_durations = vec![Duration::from_millis(40); 3];
+        durations_only.frame_rate = None;
+        let mut video = durations_only.clone();
+        let err = video.concat(&untimed).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(video, durations_only);
+    }
+
+    #[test]
+    fn test_video_dedup_consecutive() {
+        let frames = "aabaaac"
+            .chars()
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c)]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(1, 1, frames).unwrap();
+        let (dedup, counts) = video.dedup_consecutive();
+        assert_eq!(dedup.frames.len(), 4);
+        assert_eq!(counts, [2, 1, 3, 1]);
+        assert!(dedup.frame_durations.is_empty());
+
+        video.frame_rate = Some(10.0);
+        let (dedup, _) = video.dedup_consecutive();
+        assert_eq!(dedup.frame_durations[2], Duration::from_millis(300));
+        assert_eq!(dedup.duration(), video.duration());
+        assert_eq!(
+            dedup.frame_at(Duration::from_millis(450)),
+            video.frame_at(Duration::from_millis(450))
+        );
+    }
+
+    #[test]
+    fn test_video_resample_fps() {
+        let frames = "abcd"
+            .chars()
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c)]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(1, 1, frames).unwrap();
+        let chars = |v: &AsciiVideo| v.iter().map(|f| f.pixels[0].chr).collect::<String>();
+        assert!(video.resample_fps(30.0).is_err());
+
+        video.frame_rate = Some(10.0);
+        let up = video.resample_fps(25.0).unwrap();
+        assert_eq!(chars(&up), "aabbbccddd");
+        assert_eq!(up.frame_rate, Some(25.0));
+        assert_eq!(up.duration(), video.duration());
+        assert_eq!(chars(&video.resample_fps(5.0).unwrap()), "bd");
+
+        // per-frame durations: a long first frame
+        video.frame_durations = [300, 100, 100, 100].map(Duration::from_millis).to_vec();
+        let even = video.resample_fps(10.0).unwrap();
+        assert_eq!(chars(&even), "aaabcd");
+        assert!(even.frame_durations.is_empty());
+        assert!(video.resample_fps(0.0).is_err());
+
+        // more frames than a reader accepts
+        let err = video.resample_fps(1e20).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(video.resample_fps(1.0).unwrap().frames.len(), 1);
+    }
+
+    #[test]
+    fn test_sprite_from_rows() {
+        let mut first = TerminalString::from("ab");
+        first.push(TerminalChar::with_fg('c', Color::Red));
+        let sprite = AsciiSprite::from_rows(vec![first, TerminalString::from("d")]).unwrap();
+
+        assert_eq!((sprite.width, sprite.height), (3, 2));
+        assert_eq!(sprite.get_char(2, 0).unwrap().fg_color, Some(Color::Red));
+        assert_eq!(sprite.get_char(0, 1).unwrap().chr, 'd');
+        assert_eq!(sprite.get_char(2, 1), Some(TerminalChar::default()));
+
+        assert!(AsciiSprite::from_rows(vec![]).is_err());
+        assert!(AsciiSprite::from_rows(vec![TerminalString::from("")]).is_err());
+    }
+
+    #[test]
+    fn test_sprite_from_grid() {
+        let sprite = AsciiSprite::from_rows(vec!["ab".into(), "cd".into(), "ef".into()]).unwrap();
+        assert_eq!(AsciiSprite::from_grid(sprite.as_grid()).unwrap(), sprite);
+
+        let mut ragged = sprite.as_grid();
+        ragged[2].pop();
+        let err = AsciiSprite::from_grid(ragged).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("row 2"));
+        assert!(AsciiSprite::from_grid(vec![]).is_err());
+        assert!(AsciiSprite::from_grid(vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_sprite_replace() {
+        let pixels = vec![
+            TerminalChar::with_colors('a', Color::Red, Color::Red),
+            TerminalChar::with_fg('b', Color::Blue),
+            TerminalChar::with_bg('a', Color::Red),
+        ];
+        let mut sprite = AsciiSprite::new(3, 1, pixels).unwrap();
+
+        assert_eq!(sprite.replace_color(Color::Red, Color::Lime), 3);
+        assert_eq!(sprite.pixels[0].fg_color, Some(Color::Lime));
+        assert_eq!(sprite.pixels[2].bg_color, Some(Color::Lime));
+        assert_eq!(sprite.replace_color(Color::Red, Color::Lime), 0);
+
+        assert_eq!(sprite.replace_char('a', 'z'), 2);
+        assert_eq!(sprite.pixels[2], TerminalChar::with_bg('z', Color::Lime));
+
+        sprite.adjust_brightness(0.5);
+        assert_eq!(sprite.pixels[1], TerminalChar::with_fg('b', Color::Navy));
+        assert_eq!(sprite.pixels[0].bg_color, Some(Color::Green));
+
+        sprite.pixels[2].fg_color = Some(Color::Reset);
+        sprite.tint(Color::White, 0.5);
+        assert_eq!(sprite.pixels[1].fg_color, Some(Color::rgb(128, 128, 192)));
+        assert_eq!(sprite.pixels[2].fg_color, Some(Color::Reset));
+        assert_eq!(sprite.pixels[1].bg_color, None);
+    }
+
+    #[test]
+    fn test_sprite_eq_ignore_color() {
+        let plain = AsciiSprite::new(2, 1, vec![TerminalChar::from('x'); 2]).unwrap();
+        let mut themed = plain.clone();
+        themed.pixels[0] = TerminalChar::with_colors('x', Color::Red, Color::Navy);
+
+        assert!(themed.pixels[0].eq_ignore_color(&plain.pixels[0]));
+        assert!(themed.eq_ignore_color(&plain));
+        assert_ne!(themed, plain);
+
+        themed.pixels[1].chr = 'y';
+        assert!(!themed.eq_ignore_color(&plain));
+        let wide = AsciiSprite::new(1, 2, vec![TerminalChar::from('x'); 2]).unwrap();
+        assert!(!plain.eq_ignore_color(&wide));
+    }
+
+    #[test]
+    fn test_sprite_normalize() {
+        let pixels = vec![
+            TerminalChar::with_colors('a', Color::Reset, Color::Navy),
+            TerminalChar::with_colors(' ', Color::Red, Color::Navy),
+            TerminalChar::with_fg('b', Color::Red),
+            TerminalChar::with_bg(' ', Color::Reset),
+        ];
+        let mut sprite = AsciiSprite::new(4, 1, pixels).unwrap();
+        let mut buf = Vec::new();
+        sprite.write_to(&mut buf).unwrap();
+        let mut read = AsciiSprite::read_from(&mut buf.as_slice(), 4, 1).unwrap();
+        assert_ne!(read, sprite);
+
+        sprite.normalize();
+        assert_eq!(
+            sprite.pixels,
+            [
+                TerminalChar::with_bg('a', Color::Navy),
+                TerminalChar::with_bg(' ', Color::Navy),
+                TerminalChar::with_fg('b', Color::Red),
+                TerminalChar::from(' '),
+            ]
+        );
+        read.normalize();
+        assert_eq!(read, sprite);
+
+        buf.clear();
+        sprite.write_to(&mut buf).unwrap();
+        assert_eq!(
+            AsciiSprite::read_from(&mut buf.as_slice(), 4, 1).unwrap(),
+            sprite
+        );
+    }
+
+    #[test]
+    fn test_sprite_enumerate_cells() {
+        let mut sprite = AsciiSprite::new(3, 2, vec![TerminalChar::from('.'); 6]).unwrap();
+        for (x, y, c) in sprite.enumerate_cells_mut() {
+            c.chr = char::from(b'a' + (y * 3 + x) as u8);
+        }
+        let cells: Vec<_> = sprite
+            .enumerate_cells()
+            .map(|(x, y, c)| (x, y, c.chr))
+            .collect();
+        assert_eq!(cells[4], (1, 1, 'e'));
+        assert_eq!(cells.len(), 6);
+        assert_eq!(sprite.get_char(2, 0).unwrap().chr, 'c');
+    }
+
+    #[test]
+    fn test_sprite_capture_buffer() {
+        let cells = [
+            ('a', Color::Red, Color::Reset),
+            ('b', Color::Reset, Color::Reset),
+        ];
+        let sprite = AsciiSprite::capture_buffer(&cells, 2, 1).unwrap();
+        assert_eq!(sprite.pixels[0], TerminalChar::with_fg('a', Color::Red));
+        assert_eq!(sprite.pixels[1], TerminalChar::from('b'));
+        assert!(AsciiSprite::capture_buffer(&cells, 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_sprite_non_blank_cells() {
+        let mut pixels = vec![TerminalChar::default(); 12];
+        pixels[5] = TerminalChar::from('x');
+        pixels[10] = TerminalChar::with_bg(' ', Color::Red);
+        let sprite = AsciiSprite::new(4, 3, pixels).unwrap();
+
+        assert_eq!(sprite.non_blank_count(), 2);
+        assert!(!sprite.is_blank());
+        assert_eq!(sprite.bounding_box(), Some((1, 1, 2, 2)));
+
+        let blank = AsciiSprite::new(2, 2, vec![TerminalChar::default(); 4]).unwrap();
+        assert!(blank.is_blank());
+        assert_eq!(blank.bounding_box(), None);
+    }
+
+    #[test]
+    fn test_sprite_visual_width() {
+        let pixels = "a漢bc".chars().map(TerminalChar::from).collect();
+        let sprite = AsciiSprite::new(2, 2, pixels).unwrap();
+        assert_eq!(sprite.visual_width(), 3);
+        assert_eq!(TerminalChar::from('漢').display_width(), 2);
     }
 
     #[test]
@@ -468,6 +2854,16 @@ mod tests {
         assert_eq!(grid[0][1].chr, 'b');
         assert_eq!(grid[1][0].chr, 'c');
         assert_eq!(grid[1][1].chr, 'd');
+        assert_eq!(sprite.as_grid_with(Order::RowMajor), grid);
+        let columns = sprite.as_grid_with(Order::ColumnMajor);
+        assert_eq!(columns[0][1].chr, 'c');
+        assert_eq!(columns[1][0].chr, 'b');
+
+        let wide = AsciiSprite::from_rows(vec!["abc".into()]).unwrap();
+        let columns = wide.as_grid_with(Order::ColumnMajor);
+        assert_eq!(columns.len(), 3);
+        assert!(columns.iter().all(|col| col.len() == 1));
+        assert_eq!(columns[2][0].chr, 'c');
 
         assert_eq!(sprite.get_char(0, 0).unwrap().chr, 'a');
         assert_eq!(sprite.get_char(1, 0).unwrap().chr, 'b');
@@ -475,11 +2871,136 @@ mod tests {
         assert_eq!(sprite.get_char(1, 1).unwrap().chr, 'd');
         assert_eq!(sprite.get_char(2, 0), None);
         assert_eq!(sprite.get_char(0, 2), None);
+
+        assert_eq!(sprite.try_get_char(1, 1).unwrap().chr, 'd');
+        assert_eq!(sprite.index_of(1, 1), Some(3));
+        assert_eq!(sprite.index_of(2, 0), None);
+        assert_eq!(sprite.index_of(0, 2), None);
+        let err = sprite.try_get_char(2, 5).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "x 2 out of range for width 2");
+        let err = sprite.try_get_char(0, 5).unwrap_err();
+        assert_eq!(err.to_string(), "y 5 out of range for height 2");
+    }
+
+    #[test]
+    fn test_sprite_swap_colors() {
+        let pixels = vec![
+            TerminalChar::with_colors('a', Color::Red, Color::Navy),
+            TerminalChar::with_fg('b', Color::Reset),
+            TerminalChar::from('c'),
+        ];
+        let mut sprite = AsciiSprite::new(3, 1, pixels.clone()).unwrap();
+        sprite.swap_colors();
+        assert_eq!(
+            sprite.pixels,
+            [
+                TerminalChar::with_colors('a', Color::Navy, Color::Red),
+                TerminalChar::with_bg('b', Color::Reset),
+                TerminalChar::from('c'),
+            ]
+        );
+        sprite.swap_colors();
+        assert_eq!(sprite.pixels, pixels);
+    }
+
+    #[test]
+    fn test_terminal_char_validate() {
+        for chr in [
+            'a',
+            '\u{FFFD}',
+            '\u{10FFFD}',
+            '\u{E0000}',
+            '\u{D7FF}',
+            '\u{E000}',
+        ] {
+            TerminalChar::from(chr).validate().unwrap();
+        }
+        for chr in [
+            '\u{FDD0}',
+            '\u{FDEF}',
+            '\u{FFFE}',
+            '\u{FFFF}',
+            '\u{1FFFE}',
+            '\u{10FFFF}',
+        ] {
+            let c = TerminalChar::from(chr);
+            assert_eq!(c.validate().unwrap_err().kind(), io::ErrorKind::InvalidData);
+            assert!(c.write_to(&mut Vec::new()).is_err());
+        }
+
+        let sprite = AsciiSprite::new(2, 1, vec![TerminalChar::from('\u{FFFF}'); 2]).unwrap();
+        let err = sprite.write_to(&mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("U+FFFF"));
+    }
+
+    #[test]
+    fn test_terminal_char_from_tuples() {
+        let s: TerminalString = [
+            ('a', Color::Red, Color::Navy),
+            ('b', Color::Lime, Color::Black),
+        ]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+        assert_eq!(
+            s[0],
+            TerminalChar::with_colors('a', Color::Red, Color::Navy)
+        );
+        assert_eq!(
+            TerminalChar::from(('c', None, Some(Color::Olive))),
+            TerminalChar::with_bg('c', Color::Olive)
+        );
+    }
+
+    #[test]
+    fn test_blank_cell() {
+        assert_eq!(TerminalChar::default(), TerminalChar::BLANK);
+        assert!(TerminalChar::from(' ').is_blank());
+        assert!(!TerminalChar::from('x').is_blank());
+
+        let reset = TerminalChar::with_colors(' ', Color::reset(), Color::reset());
+        assert!(!reset.is_blank());
+        assert!(reset.normalized().is_blank());
+        assert!(
+            TerminalChar::with_fg(' ', Color::Red)
+                .normalized()
+                .is_blank()
+        );
+        assert!(
+            !TerminalChar::with_bg(' ', Color::Black)
+                .normalized()
+                .is_blank()
+        );
+        assert_ne!(Color::reset(), Color::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_try_from_char_rejects_control() {
+        assert_eq!(TerminalChar::try_from_char('a').unwrap().chr, 'a');
+        assert!(TerminalChar::try_from_char('█').unwrap().is_printable());
+        for c in ['\n', '\x1b', '\x7f', '\u{9b}'] {
+            assert!(TerminalChar::try_from_char(c).is_err());
+            assert!(!TerminalChar::from_char(c).is_printable());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_terminal_char_dedup() {
+        let cells = [
+            TerminalChar::with_fg('a', Color::Red),
+            TerminalChar::with_fg('a', Color::rgb(255, 0, 0)),
+            TerminalChar::with_fg('a', Color::Reset),
+            TerminalChar::from('a'),
+        ];
+        let unique: std::collections::HashSet<_> = cells.iter().collect();
+        assert_eq!(unique.len(), 3);
     }
 
     #[test]
     fn fuzz_terminal_char_roundtrip() {
-        let mut rng = rand::rng();
+        let mut rng = ChaCha8Rng::seed_from_u64(0x5eed_0001);
 
         for _ in 0..1000 {
             let u = rng.random_range(32u8..=126u8);
@@ -513,15 +3034,614 @@ mod tests {
 
             let mut buf = Vec::new();
             pc.write_to(&mut buf).unwrap();
-            let mut cur = std::io::Cursor::new(buf);
-            let pc2 = TerminalChar::read_from(&mut cur).unwrap();
+            let pc2 = TerminalChar::read_from(&mut buf.as_slice()).unwrap();
             assert_eq!(pc, pc2);
         }
     }
 
+    #[test]
+    fn test_varint_char_encoding() {
+        for chr in [
+            'a',
+            '\u{7F}',
+            '\u{80}',
+            'ß',
+            '█',
+            '\u{FFFD}',
+            '😀',
+            // the highest code point that isn't a noncharacter
+            '\u{10FFFD}',
+        ] {
+            let c = TerminalChar::with_fg(chr, Color::Red);
+            let mut buf = Vec::new();
+            c.write_encoded(&mut buf, CharEncoding::Varint).unwrap();
+            let expected_len = match chr as u32 {
+                0..0x80 => 1,
+                0x80..0x4000 => 2,
+                _ => 3,
+            };
+            assert_eq!(buf.len(), expected_len + 4 + 1);
+            let len = codec::cell_len(&buf, CharEncoding::Varint).unwrap();
+            assert_eq!(len, buf.len());
+            assert_eq!(c.serialized_size(CharEncoding::Varint), buf.len());
+            let back = TerminalChar::read_encoded(&mut buf.as_slice(), CharEncoding::Varint);
+            assert_eq!(back.unwrap(), c);
+        }
+
+        // surrogate code point and a value past u32::MAX
+        for bad in [&[0x80, 0xB0, 0x03][..], &[0xFF, 0xFF, 0xFF, 0xFF, 0x7F]] {
+            let err = TerminalChar::read_encoded(&mut &bad[..], CharEncoding::Varint);
+            assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+
+        let frame = AsciiSprite::new(4, 1, vec![TerminalChar::from('#'); 4]).unwrap();
+        let mut video = AsciiVideo::new(4, 1, vec![frame; 10]).unwrap();
+        let fixed = video.to_bytes().unwrap();
+        video.char_encoding = CharEncoding::Varint;
+        let varint = video.to_bytes().unwrap();
+        assert_eq!(fixed.len() - varint.len(), 10 * 4 * 3 - 6);
+        assert_eq!(AsciiVideo::from_bytes(&varint).unwrap(), video);
+    }
+
+    #[test]
+    fn test_terminal_string_roundtrip() {
+        let mut s = TerminalString::from("hi ");
+        s.0.push(TerminalChar::with_colors(
+            '!',
+            Color::Red,
+            Color::rgb(1, 2, 3),
+        ));
+
+        let mut buf = Vec::new();
+        s.write_to(&mut buf).unwrap();
+        assert_eq!(&buf[..4], &4u32.to_le_bytes());
+        assert_eq!(TerminalString::read_from(&mut buf.as_slice()).unwrap(), s);
+
+        let err = TerminalString::read_from(&mut &u32::MAX.to_le_bytes()[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_terminal_string_display() {
+        let mut s = TerminalString::from("a");
+        s.push(TerminalChar::with_fg('b', Color::rgb(1, 2, 3)));
+        s.push(TerminalChar::with_colors(
+            'c',
+            Color::rgb(1, 2, 3),
+            Color::Reset,
+        ));
+        s.push(TerminalChar::with_bg('d', Color::Navy));
+        s.extend_from_str("e");
+
+        assert_eq!(s.len(), 5);
+        assert_eq!(s.as_plain_string(), "abcde");
+        assert_eq!(
+            s.to_string(),
+            "a\x1b[38;2;1;2;3mbc\x1b[0;48;2;0;0;128md\x1b[0me"
+        );
+        assert_eq!(TerminalString::from("plain").to_string(), "plain");
+        let tail = TerminalString::from_iter([TerminalChar::with_fg('x', Color::Red)]);
+        assert_eq!(tail.to_string(), "\x1b[38;2;255;0;0mx\x1b[0m");
+    }
+
+    #[test]
+    fn test_sprite_to_plain_string() {
+        let mut sprite = AsciiSprite::from_rows(vec!["ab".into(), "c".into()]).unwrap();
+        sprite.pixels[0].fg_color = Some(Color::Red);
+        assert_eq!(sprite.to_plain_string(), "ab\nc ");
+    }
+
+    #[test]
+    fn test_sprite_debug_dump() {
+        let mut sprite = AsciiSprite::from_rows(vec!["a \x1b".into(), " b".into()]).unwrap();
+        sprite.pixels[2].chr = ' ';
+        sprite.pixels[2].bg_color = Some(Color::Navy);
+        sprite.pixels[1].bg_color = Some(Color::Reset);
+        sprite.pixels[4].chr = '\x1b';
+        assert_eq!(sprite.debug_dump(), "  0 1 2\n0 a · ␣\n1 · \u{FFFD} ·");
+
+        let wide = AsciiSprite::new(11, 1, vec![TerminalChar::from('x'); 11]).unwrap();
+        let dump = wide.debug_dump();
+        assert!(dump.starts_with("  0  1  2 "));
+        assert!(dump.ends_with("9  10\n0 x  x  x  x  x  x  x  x  x  x  x "));
+    }
+
+    #[test]
+    fn test_render_color_depths() {
+        let mut s =
+            TerminalString::from_iter([TerminalChar::with_colors('a', Color::Red, Color::Navy)]);
+        s.push(TerminalChar::with_bg('b', Color::rgb(250, 250, 240)));
+        assert_eq!(
+            s.to_ansi(ColorDepth::TrueColor),
+            "\x1b[38;2;255;0;0;48;2;0;0;128ma\x1b[0;48;2;250;250;240mb\x1b[0m"
+        );
+        assert_eq!(
+            s.to_ansi(ColorDepth::Ansi256),
+            "\x1b[38;5;196;48;5;18ma\x1b[0;48;5;231mb\x1b[0m"
+        );
+        assert_eq!(
+            s.to_ansi(ColorDepth::Ansi16),
+            "\x1b[91;44ma\x1b[0;107mb\x1b[0m"
+        );
+
+        let sprite = AsciiSprite::from_rows(vec![s.clone(), TerminalString::from("cd")]).unwrap();
+        assert_eq!(
+            sprite.to_ansi(ColorDepth::Ansi16),
+            "\x1b[91;44ma\x1b[0;107mb\x1b[0m\ncd"
+        );
+    }
+
+    fn header(version: u8, width: u16, height: u16, frames: u32) -> Vec<u8> {
+        let mut buf = b"ASCV".to_vec();
+        buf.push(version);
+        buf.extend_from_slice(&width.to_le_bytes());
+        buf.extend_from_slice(&height.to_le_bytes());
+        buf.extend_from_slice(&frames.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn test_serialized_sizes() {
+        let full = TerminalChar::with_colors('x', Color::Red, Color::Navy);
+        let plain = TerminalChar::from('y');
+        let sprite = AsciiSprite::new(2, 1, vec![full, plain]).unwrap();
+        let mut buf = Vec::new();
+        full.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), TerminalChar::SERIALIZED_SIZE);
+        buf.clear();
+        sprite.write_to(&mut buf).unwrap();
+        assert_eq!(sprite.serialized_size(), buf.len());
+        assert_eq!(sprite.serialized_size(), 12 + 6);
+
+        let mut video = AsciiVideo::new(2, 1, vec![sprite.clone(); 3]).unwrap();
+        video.metadata.insert("title".into(), "sizes".into());
+        let bytes = video.to_bytes().unwrap();
+        let ext_len = u32::from_le_bytes(bytes[14..18].try_into().unwrap()) as usize;
+        assert_eq!(
+            bytes.len(),
+            AsciiVideo::HEADER_SIZE + ext_len + 3 * sprite.serialized_size()
+        );
+    }
+
+    #[test]
+    fn test_read_version_1() {
+        let mut buf = header(1, 1, 1, 1);
+        TerminalChar::from('v').write_to(&mut buf).unwrap();
+
+        let video = AsciiVideo::read_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(video.frames[0].pixels[0].chr, 'v');
+    }
+
+    #[test]
+    fn test_read_newer_minor_version() {
+        let mut buf = header(2, 1, 1, 1);
+        buf.push(AsciiVideo::MINOR_VERSION + 3);
+        // a single section with a tag from the future
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        buf.push(0x7F);
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&[0xAA, 0xBB, 0xCC]);
+        TerminalChar::from('v').write_to(&mut buf).unwrap();
+
+        let video = AsciiVideo::read_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(video.frames[0].pixels[0].chr, 'v');
+    }
+
+    #[test]
+    fn test_reject_oversized_frame_count() {
+        let mut buf = header(1, 4096, 4096, 100_000);
+        buf.extend_from_slice(&[0; 64]);
+        let err = AsciiVideo::from_bytes(&buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("100000 frames of 4096x4096"));
+
+        // the check is a lower bound, colourless cells pass it
+        let mut buf = header(1, 2, 2, 3);
+        let frame = AsciiSprite::new(2, 2, vec![TerminalChar::from('.'); 4]).unwrap();
+        for _ in 0..3 {
+            frame.write_to(&mut buf).unwrap();
+        }
+        assert_eq!(AsciiVideo::from_bytes(&buf).unwrap().frames.len(), 3);
+        buf.pop();
+        let err = AsciiVideo::from_bytes(&buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_read_limits() {
+        let frame = AsciiSprite::new(8, 2, vec![TerminalChar::from('#'); 16]).unwrap();
+        let video = AsciiVideo::new(8, 2, vec![frame; 5]).unwrap();
+        let bytes = video.to_bytes().unwrap();
+        let read = |limits| AsciiVideo::read_from_with_limits(&mut bytes.as_slice(), limits);
+
+        let tight = ReadLimits {
+            max_width: 8,
+            max_height: 2,
+            max_frames: 5,
+            max_total_bytes: 5 * 16 * size_of::<TerminalChar>() as u64,
+            ..ReadLimits::default()
+        };
+        assert_eq!(read(tight).unwrap(), video);
+        for limits in [
+            ReadLimits {
+                max_width: 7,
+                ..tight
+            },
+            ReadLimits {
+                max_height: 1,
+                ..tight
+            },
+            ReadLimits {
+                max_frames: 4,
+                ..tight
+            },
+            ReadLimits {
+                max_total_bytes: tight.max_total_bytes - 1,
+                ..tight
+            },
+        ] {
+            let err = read(limits).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // larger than the default caps, which `new` enforces as well
+        let frames = vec![AsciiSprite::new(5000, 1, vec![TerminalChar::default(); 5000]).unwrap()];
+        let err = AsciiVideo::new(5000, 1, frames.clone()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        for (w, h) in [(0, 0), (0, 1), (1, 0)] {
+            let err = AsciiVideo::new(w, h, vec![]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        let wide = AsciiVideo::new_unlimited(5000, 1, frames).unwrap();
+        let wide_bytes = wide.to_bytes().unwrap();
+        assert!(AsciiVideo::from_bytes(&wide_bytes).is_err());
+        let loose = ReadLimits {
+            max_width: u16::MAX,
+            ..ReadLimits::default()
+        };
+        let read = AsciiVideo::read_from_with_limits(&mut wide_bytes.as_slice(), loose);
+        assert_eq!(read.unwrap(), wide);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_progress() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('#')]).unwrap();
+        let video = AsciiVideo::new(1, 1, vec![frame; 600]).unwrap();
+        let path = "test_file_progress.bin";
+        let mut written = Vec::new();
+        video
+            .write_to_file_with_progress(path, |done, total| written.push((done, total)))
+            .unwrap();
+        let mut read = Vec::new();
+        let loaded =
+            AsciiVideo::read_from_file_with_progress(path, |done, total| read.push((done, total)));
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.unwrap(), video);
+        assert_eq!(written, [(0, 600), (256, 600), (512, 600), (600, 600)]);
+        assert_eq!(read, written);
+    }
+
+    #[test]
+    fn test_frame_byte_ranges() {
+        let plain = AsciiSprite::new(2, 1, vec![TerminalChar::from('a'); 2]).unwrap();
+        let mut coloured = plain.clone();
+        coloured.pixels[0] = TerminalChar::with_fg('é', Color::Red);
+        coloured.pixels[1] = TerminalChar::with_bg('b', Color::ansi256_exact(200));
+        let mut video = AsciiVideo::new(2, 1, vec![plain.clone(), coloured, plain]).unwrap();
+        video.set_metadata("title", "ranges");
+
+        for encoding in [CharEncoding::Fixed, CharEncoding::Varint] {
+            video.char_encoding = encoding;
+            let bytes = video.to_bytes().unwrap();
+            let offsets = video.frame_offsets().unwrap();
+            assert_eq!(offsets.len(), 3);
+            for (i, frame) in video.frames.iter().enumerate() {
+                let range = video.frame_byte_range(i).unwrap();
+                assert_eq!(range.start, offsets[i]);
+                let cells = &bytes[range.start as usize..range.end as usize];
+                let read = AsciiSprite::read_encoded(&mut &cells[..], 2, 1, encoding).unwrap();
+                assert_eq!(&read, frame);
+            }
+            assert_eq!(video.frame_byte_range(2).unwrap().end, bytes.len() as u64);
+        }
+        let err = video.frame_byte_range(3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // offsets of a video that can't be written aren't made up
+        video.frame_durations = vec![Duration::MAX; 3];
+        assert!(video.frame_offsets().is_err());
+        assert!(video.frame_byte_range(0).is_err());
+    }
+
+    #[test]
+    fn test_reject_unsupported_version() {
+        for version in [0, AsciiVideo::VERSION + 1] {
+            let buf = header(version, 1, 1, 0);
+            let err = AsciiVideo::read_from(&mut buf.as_slice()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('m')]).unwrap();
+        let mut video = AsciiVideo::new(1, 1, vec![frame]).unwrap();
+        assert_eq!(video.set_metadata("title", "demo"), None);
+        assert_eq!(
+            video.set_metadata("title", "Demo"),
+            Some("demo".to_string())
+        );
+        video.set_metadata("created", "2024-01-01T00:00:00Z");
+
+        let mut buf = Vec::new();
+        video.write_to(&mut buf).unwrap();
+        let loaded = AsciiVideo::read_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(loaded.get_metadata("title"), Some("Demo"));
+        assert_eq!(loaded, video);
+        assert_eq!(video.slice(0, 1).unwrap().metadata, video.metadata);
+    }
+
+    #[test]
+    fn test_frame_labels() {
+        let frames = (b'a'..=b'd')
+            .map(|c| AsciiSprite::new(1, 1, vec![TerminalChar::from(c as char)]).unwrap())
+            .collect();
+        let mut video = AsciiVideo::new(1, 1, frames).unwrap();
+        let mut unlabelled = Vec::new();
+        video.write_to(&mut unlabelled).unwrap();
+
+        assert_eq!(video.set_label(1, "idle").unwrap(), None);
+        assert_eq!(video.set_label(3, "jump").unwrap(), None);
+        assert_eq!(
+            video.set_label(3, "jump_start").unwrap().as_deref(),
+            Some("jump")
+        );
+        assert!(video.set_label(4, "past").is_err());
+        assert_eq!(video.labels.len(), 4);
+        assert_eq!(video.frame_by_label("jump_start"), Some(3));
+        assert_eq!(video.frame_by_label("jump"), None);
+        assert_eq!(video.label(1), Some("idle"));
+
+        let mut buf = Vec::new();
+        video.write_to(&mut buf).unwrap();
+        assert!(buf.len() > unlabelled.len());
+        assert_eq!(AsciiVideo::read_from(&mut buf.as_slice()).unwrap(), video);
+        assert!(
+            AsciiVideo::read_from(&mut unlabelled.as_slice())
+                .unwrap()
+                .labels
+                .is_empty()
+        );
+
+        assert_eq!(video.slice(1, 3).unwrap().frame_by_label("idle"), Some(0));
+        assert_eq!(
+            video.downsample(2).unwrap().labels,
+            [Some("idle".into()), Some("jump_start".into())]
+        );
+        let mut joined = AsciiVideo::new(1, 1, video.frames[..1].to_vec()).unwrap();
+        joined.concat(&video).unwrap();
+        assert_eq!(joined.frame_by_label("idle"), Some(2));
+        assert_eq!(joined.remove_label(2).as_deref(), Some("idle"));
+        assert_eq!(joined.label(2), None);
+
+        video.labels.pop();
+        assert!(video.write_to(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_indexed_colors_roundtrip() {
+        let cell = TerminalChar::with_colors('#', Color::ansi256_exact(1), Color::Navy);
+        let frame = AsciiSprite::new(2, 1, vec![cell, TerminalChar::from('.')]).unwrap();
+        let mut video = AsciiVideo::new(2, 1, vec![frame.clone()]).unwrap();
+
+        let mut buf = Vec::new();
+        video.write_to(&mut buf).unwrap();
+        let read = AsciiVideo::read_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(read, video);
+        assert_eq!(read.frames[0].pixels[0].fg_to_ansi256(), Some(1));
+
+        // the header marks palette indices, so older readers refuse the file
+        let (_, ext) = AsciiVideo::read_header(&mut buf.as_slice(), ReadLimits::default()).unwrap();
+        assert!(ext.indexed_colors);
+        video.frames[0].pixels[0].fg_color = Some(Color::Maroon);
+        buf.clear();
+        video.write_to(&mut buf).unwrap();
+        let (_, ext) = AsciiVideo::read_header(&mut buf.as_slice(), ReadLimits::default()).unwrap();
+        assert!(!ext.indexed_colors);
+
+        // an index that no longer matches the RGB value isn't written
+        let mut edited = Color::ansi256_exact(1);
+        edited.rgb = (255, 0, 0);
+        video.frames[0].pixels[0].fg_color = Some(edited);
+        let read = AsciiVideo::from_bytes(&video.to_bytes().unwrap()).unwrap();
+        assert_eq!(read.frames[0].pixels[0].fg_color, Some(Color::Red));
+
+        // outside of videos, the index is stored as its RGB value
+        let mut sprite_buf = Vec::new();
+        frame.write_to(&mut sprite_buf).unwrap();
+        assert_eq!(sprite_buf.len(), frame.serialized_size());
+        assert_eq!(sprite_buf[4..8], [1, 128, 0, 0]);
+        let mut plain = frame.clone();
+        plain.pixels[0].fg_color = Some(Color::Maroon);
+        assert_eq!(
+            AsciiSprite::read_from(&mut sprite_buf.as_slice(), 2, 1).unwrap(),
+            plain
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_file_roundtrip() {
+        let frames = (0..20u8)
+            .map(|i| {
+                let mut pixels = vec![TerminalChar::with_fg('.', Color::Grey); 80 * 24];
+                pixels[i as usize] =
+                    TerminalChar::with_colors('@', Color::Red, Color::rgb(i, 0, 0));
+                AsciiSprite::new(80, 24, pixels).unwrap()
+            })
+            .collect();
+        let video = AsciiVideo::new(80, 24, frames).unwrap();
+
+        let path = "test_gzip_video.bin.gz";
+        video.write_to_file_gz(path).unwrap();
+        let size = std::fs::metadata(path).unwrap().len();
+        let gz = AsciiVideo::read_from_file_gz(path);
+        let detected = AsciiVideo::read_from_file(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(gz.unwrap(), video);
+        assert_eq!(detected.unwrap(), video);
+        let mut raw = Vec::new();
+        video.write_to(&mut raw).unwrap();
+        assert!(size * 5 < raw.len() as u64);
+
+        // trailing data past the declared frames isn't inflated
+        raw.resize(raw.len() + 2_000_000, 0);
+        let bomb = "test_gzip_bomb.bin.gz";
+        std::fs::write(bomb, gzip::gzip(&raw)).unwrap();
+        let read = AsciiVideo::read_from_file(bomb);
+        let thumbnail = AsciiVideo::read_thumbnail(bomb);
+        std::fs::remove_file(bomb).unwrap();
+        assert_eq!(read.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(thumbnail.unwrap(), video.frames[0]);
+
+        // a header claiming huge frames doesn't raise the cap: 3 MB of
+        // valid cells compress to a few KB
+        let mut raw = Vec::new();
+        AsciiVideo::write_header(&mut raw, 4096, 4096, 1000, &[]).unwrap();
+        raw.resize(raw.len() + 3_000_000, 0);
+        std::fs::write(bomb, gzip::gzip(&raw)).unwrap();
+        let limits = ReadLimits {
+            max_decompressed_bytes: 1_000_000,
+            ..ReadLimits::default()
+        };
+        let read = AsciiVideo::read_from_file_with_limits(bomb, limits);
+        let size = std::fs::metadata(bomb).unwrap().len();
+        std::fs::remove_file(bomb).unwrap();
+        assert!(size < 20_000);
+        let err = read.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("exceeds 1000000 bytes"), "{}", err);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_lenient() {
+        let frames = (0..4u8)
+            .map(|i| {
+                AsciiSprite::new(
+                    3,
+                    2,
+                    vec![TerminalChar::with_fg('#', Color::rgb(i, 0, 0)); 6],
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut video = AsciiVideo::new(3, 2, frames).unwrap();
+        video.frame_durations = vec![Duration::from_millis(40); 4];
+        let bytes = video.to_bytes().unwrap();
+        let frame_len = video.frames[0].serialized_size();
+
+        let (full, truncated) = AsciiVideo::read_lenient(&mut bytes.as_slice()).unwrap();
+        assert_eq!((full, truncated), (video.clone(), false));
+
+        // cut halfway through the third frame
+        let cut = &bytes[..bytes.len() - frame_len - frame_len / 2];
+        assert!(AsciiVideo::from_bytes(cut).is_err());
+        let (partial, truncated) = AsciiVideo::read_lenient(&mut &cut[..]).unwrap();
+        assert!(truncated);
+        assert_eq!(partial.frames, video.frames[..2]);
+        assert_eq!(partial.frame_durations.len(), 2);
+
+        let path = "test_read_lenient.ascv";
+        std::fs::write(path, cut).unwrap();
+        let from_file = AsciiVideo::read_from_file_lenient(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(from_file.unwrap().0, partial);
+
+        assert!(AsciiVideo::read_lenient(&mut &bytes[..10]).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_thumbnail() {
+        let first = AsciiSprite::new(2, 1, vec![TerminalChar::from('1'); 2]).unwrap();
+        let second = AsciiSprite::new(2, 1, vec![TerminalChar::from('2'); 2]).unwrap();
+        let video = AsciiVideo::new(2, 1, vec![first.clone(), second]).unwrap();
+
+        let path = "test_thumbnail_video.bin";
+        video.write_to_file(path).unwrap();
+        let thumb = AsciiVideo::read_thumbnail(path);
+        #[cfg(feature = "gzip")]
+        {
+            video.write_to_file_gz(path).unwrap();
+            let thumb_gz = AsciiVideo::read_thumbnail(path);
+            assert_eq!(thumb_gz.unwrap(), first);
+        }
+        AsciiVideo::new(2, 1, vec![])
+            .unwrap()
+            .write_to_file(path)
+            .unwrap();
+        let empty = AsciiVideo::read_thumbnail(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(thumb.unwrap(), first);
+        assert_eq!(empty.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(all(feature = "std", not(feature = "gzip")))]
+    #[test]
+    fn test_gzip_disabled() {
+        let path = "test_gzip_disabled.bin.gz";
+        std::fs::write(path, [0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 255]).unwrap();
+        let err = AsciiVideo::read_from_file(path).unwrap_err();
+        let thumb = AsciiVideo::read_thumbnail(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(thumb.unwrap_err().kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_probe() {
+        let frame = AsciiSprite::new(3, 2, vec![TerminalChar::from('p'); 6]).unwrap();
+        let video = AsciiVideo::new(3, 2, vec![frame.clone(), frame]).unwrap();
+        let path = "test_probe_video.bin";
+        video.write_to_file(path).unwrap();
+        let info = probe(path);
+        std::fs::write(path, AsciiVideo::MAGIC).unwrap();
+        let short = probe(path);
+        std::fs::remove_file(path).unwrap();
+
+        let info = info.unwrap();
+        assert_eq!(info.version, AsciiVideo::VERSION);
+        assert_eq!((info.width, info.height, info.frame_count), (3, 2, 2));
+        assert_eq!(short.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let frame =
+            AsciiSprite::new(2, 2, vec![TerminalChar::with_fg('b', Color::Aqua); 4]).unwrap();
+        let mut video = AsciiVideo::new(2, 2, vec![frame; 3]).unwrap();
+        video.set_metadata("title", "bytes");
+
+        let bytes = video.to_bytes().unwrap();
+        let mut expected = Vec::new();
+        video.write_to(&mut expected).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(AsciiVideo::from_bytes(&bytes).unwrap(), video);
+
+        let err = AsciiVideo::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn fuzz_ascii_video_roundtrip() {
-        let mut rng = rand::rng();
+        let mut rng = ChaCha8Rng::seed_from_u64(0x5eed_0002);
 
         for _ in 0..200 {
             let width = rng.random_range(1u16..5);
@@ -563,11 +3683,39 @@ mod tests {
                 frames.push(AsciiSprite::new(width, height, frame).unwrap());
             }
 
-            let video = AsciiVideo {
+            let mut video = AsciiVideo {
                 width,
                 height,
                 frames,
+                metadata: BTreeMap::new(),
+                frame_rate: None,
+                frame_durations: Vec::new(),
+                looping: false,
+                labels: Vec::new(),
+                char_encoding: if rng.random_bool(0.5) {
+                    CharEncoding::Varint
+                } else {
+                    CharEncoding::Fixed
+                },
             };
+            if rng.random_bool(0.5) {
+                video.frame_rate = Some(rng.random_range(1.0..60.0));
+                video.looping = rng.random_bool(0.5);
+            }
+            if rng.random_bool(0.3) {
+                video.frame_durations = (0..video.frames.len())
+                    .map(|_| Duration::from_millis(rng.random_range(0..5000)))
+                    .collect();
+            }
+            if rng.random_bool(0.3) {
+                video.labels = (0..video.frames.len())
+                    .map(|i| rng.random_bool(0.5).then(|| format!("frame {}", i)))
+                    .collect();
+            }
+            if rng.random_bool(0.5) {
+                video.set_metadata("title", "fuzz");
+                video.set_metadata("author", "ß✓");
+            }
             let path = "test_fuzz_video.bin";
             video.write_to_file(path).unwrap();
             let loaded = AsciiVideo::read_from_file(path).unwrap();
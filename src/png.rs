@@ -0,0 +1,165 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    AsciiVideo, TerminalChar,
+    deflate::{crc32, crc32_update, zlib},
+    raster::RgbaImage,
+    with_path,
+};
+
+impl AsciiVideo {
+    /// Export every frame as a PNG image into the directory `dir`, which is
+    /// created if it doesn't exist yet.
+    ///
+    /// Frames are rendered with [`crate::AsciiSprite::render_rgba`] and named
+    /// after their position starting at 1, zero-padded to at least 4 digits
+    /// so they sort correctly: `frame_0001.png`, `frame_0002.png`, ...
+    /// Existing files with the same names are overwritten.
+    ///
+    /// ## Error
+    /// if a cell dimension is 0, the directory can't be created, or writing
+    /// a file fails; the message then names the file
+    pub fn export_png_sequence<F>(
+        &self,
+        dir: impl AsRef<Path>,
+        cell_w: u32,
+        cell_h: u32,
+        glyph: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(&TerminalChar, &mut RgbaImage, u32, u32),
+    {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(|e| with_path(e, dir))?;
+        let digits = self.frames.len().to_string().len().max(4);
+        for (i, frame) in self.frames.iter().enumerate() {
+            let img = frame.render_rgba(cell_w, cell_h, &glyph)?;
+            let path = dir.join(format!("frame_{:0digits$}.png", i + 1));
+            write_png_file(&path, &img).map_err(|e| with_path(e, &path))?;
+        }
+        Ok(())
+    }
+}
+
+fn write_png_file(path: &Path, img: &RgbaImage) -> io::Result<()> {
+    let f = File::create(path)?;
+    let mut w = BufWriter::new(f);
+    write_png(&mut w, img)?;
+    w.flush()
+}
+
+/// Encode `img` as an 8-bit RGBA PNG without scanline filtering.
+fn write_png<W: Write>(w: &mut W, img: &RgbaImage) -> io::Result<()> {
+    w.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&img.width().to_be_bytes());
+    ihdr.extend_from_slice(&img.height().to_be_bytes());
+    // bit depth 8, colour type RGBA, default compression, filter and interlacing
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+    write_chunk(w, b"IHDR", &ihdr)?;
+
+    let stride = img.width() as usize * 4;
+    let mut scanlines = Vec::with_capacity((stride + 1) * img.height() as usize);
+    for row in img.as_raw().chunks_exact(stride.max(1)) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+    write_chunk(w, b"IDAT", &zlib(&scanlines))?;
+    write_chunk(w, b"IEND", &[])
+}
+
+fn write_chunk<W: Write>(w: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+    let crc = crc32_update(crc32(kind), data);
+    w.write_all(&crc.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsciiSprite, Color, raster::block_glyph};
+
+    /// Split a PNG into its chunks, checking every CRC.
+    fn chunks(mut png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        png = &png[8..];
+        let mut out = Vec::new();
+        while !png.is_empty() {
+            let len = u32::from_be_bytes(png[..4].try_into().unwrap()) as usize;
+            let kind: [u8; 4] = png[4..8].try_into().unwrap();
+            let data = png[8..8 + len].to_vec();
+            let crc = u32::from_be_bytes(png[8 + len..12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32_update(crc32(&kind), &data));
+            out.push((kind, data));
+            png = &png[12 + len..];
+        }
+        out
+    }
+
+    #[test]
+    fn test_png_encoding() {
+        let sprite = AsciiSprite::new(
+            2,
+            1,
+            vec![
+                TerminalChar::with_colors('#', Color::Red, Color::Navy),
+                TerminalChar::from(' '),
+            ],
+        )
+        .unwrap();
+        let img = sprite.render_rgba(3, 2, block_glyph(3, 2)).unwrap();
+        let mut png = Vec::new();
+        write_png(&mut png, &img).unwrap();
+
+        let chunks = chunks(&png);
+        let kinds: Vec<_> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 6, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+
+        #[cfg(feature = "gzip")]
+        {
+            let (pixels, _) = crate::gzip::decompress(&chunks[1].1[2..], usize::MAX).unwrap();
+            let expected: Vec<u8> = img
+                .as_raw()
+                .chunks(6 * 4)
+                .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+                .collect();
+            assert_eq!(pixels, expected);
+        }
+    }
+
+    #[test]
+    fn test_export_png_sequence() {
+        let frame = AsciiSprite::new(1, 1, vec![TerminalChar::from('x')]).unwrap();
+        let video = AsciiVideo::new(1, 1, vec![frame; 3]).unwrap();
+        let dir = "test_png_sequence/nested";
+
+        let result = video.export_png_sequence(dir, 2, 2, block_glyph(2, 2));
+        let mut names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        let blocked = Path::new(dir).join("frame_0002.png");
+        fs::remove_file(&blocked).unwrap();
+        fs::create_dir(&blocked).unwrap();
+        let err = video
+            .export_png_sequence(dir, 2, 2, block_glyph(2, 2))
+            .unwrap_err();
+        fs::remove_dir_all("test_png_sequence").unwrap();
+
+        result.unwrap();
+        names.sort();
+        assert_eq!(
+            names,
+            ["frame_0001.png", "frame_0002.png", "frame_0003.png"]
+        );
+        assert!(err.to_string().contains("frame_0002.png"));
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::io;
+
+/// Characters ordered from darkest to brightest, mapping a brightness to
+/// the character that best represents it.
+///
+/// Any string of at least one character can be a ramp, see
+/// [`Ramp::from_str`], or use one of the presets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ramp {
+    chars: Vec<char>,
+}
+
+impl Ramp {
+    /// Plain ASCII, `" .:-=+*#%@"`.
+    pub fn classic() -> Self {
+        Self::preset(" .:-=+*#%@")
+    }
+
+    /// Shade blocks, `" ░▒▓█"`.
+    pub fn blocks() -> Self {
+        Self::preset(" ░▒▓█")
+    }
+
+    /// Braille patterns with an increasing number of dots, `" ⠁⠃⠇⡇⡏⡟⡿⣿"`.
+    pub fn braille() -> Self {
+        Self::preset(" ⠁⠃⠇⡇⡏⡟⡿⣿")
+    }
+
+    fn preset(chars: &str) -> Self {
+        Self {
+            chars: chars.chars().collect(),
+        }
+    }
+
+    /// ## Error
+    /// if `chars` is empty
+    pub fn new(chars: Vec<char>) -> io::Result<Self> {
+        if chars.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "brightness ramp must not be empty",
+            ));
+        }
+        Ok(Self { chars })
+    }
+
+    /// The characters of the ramp, darkest first.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// The character for `brightness` in `0.0..=1.0`, values outside are clamped.
+    pub fn char_for(&self, brightness: f32) -> char {
+        self.char_at_level(brightness.clamp(0.0, 1.0) * self.max_level())
+    }
+
+    /// Highest level accepted by [`Ramp::char_at_level`].
+    pub(crate) fn max_level(&self) -> f32 {
+        (self.chars.len() - 1) as f32
+    }
+
+    /// The character nearest to a brightness already scaled to `0.0..=max_level`.
+    pub(crate) fn char_at_level(&self, level: f32) -> char {
+        let idx = (level + 0.5) as usize;
+        self.chars[idx.min(self.chars.len() - 1)]
+    }
+}
+
+impl Default for Ramp {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl FromStr for Ramp {
+    type Err = io::Error;
+
+    /// Use the characters of `s` as a ramp, darkest first.
+    ///
+    /// ## Error
+    /// if `s` is empty
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::new(s.chars().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_mapping() {
+        let ramp: Ramp = " .#".parse().unwrap();
+        assert_eq!(ramp.char_for(0.0), ' ');
+        assert_eq!(ramp.char_for(0.3), '.');
+        assert_eq!(ramp.char_for(0.8), '#');
+        assert_eq!(ramp.char_for(7.0), '#');
+        assert_eq!(ramp.char_for(-1.0), ' ');
+        assert_eq!(ramp.char_for(f32::NAN), ' ');
+
+        let single: Ramp = "x".parse().unwrap();
+        assert_eq!(single.char_for(0.7), 'x');
+        assert!("".parse::<Ramp>().is_err());
+
+        for preset in [Ramp::classic(), Ramp::blocks(), Ramp::braille()] {
+            assert_eq!(preset.char_for(0.0), ' ');
+            assert_eq!(preset.char_for(1.0), *preset.chars().last().unwrap());
+        }
+        assert_eq!(Ramp::blocks().char_for(0.5), '▒');
+    }
+}
@@ -0,0 +1,237 @@
+use alloc::{format, vec, vec::Vec};
+
+use crate::{AsciiSprite, Color, TerminalChar, io};
+
+/// Colour used for a foreground that is `None` or a reset.
+pub const DEFAULT_FG: Color = Color::White;
+/// Colour used for a background that is `None` or a reset.
+pub const DEFAULT_BG: Color = Color::Black;
+
+/// A plain RGBA8 pixel buffer, row-major, 4 bytes per pixel.
+///
+/// Mirrors the parts of `image::RgbaImage` this crate needs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Create a fully transparent black image.
+    ///
+    /// ## Panics
+    /// if the image is too large to address, see [`RgbaImage::try_new`]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::try_new(width, height).unwrap()
+    }
+
+    /// Create a fully transparent black image.
+    ///
+    /// ## Error
+    /// `InvalidInput` if its `width * height * 4` bytes don't fit in a `usize`
+    pub fn try_new(width: u32, height: u32) -> io::Result<Self> {
+        let len = byte_len(width, height).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}x{} image is too large", width, height),
+            )
+        })?;
+        Ok(Self {
+            width,
+            height,
+            data: vec![0; len],
+        })
+    }
+
+    /// Wrap an existing RGBA8 buffer.
+    ///
+    /// ## return
+    /// `None` if `data` isn't exactly `width * height * 4` bytes long
+    pub fn from_raw(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if byte_len(width, height) != Some(data.len()) {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw RGBA8 bytes.
+    pub fn as_raw(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Get the pixel at the given coordinates.
+    ///
+    /// ## Panics
+    /// if the coordinates are out of bounds
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.offset(x, y);
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    /// Set the pixel at the given coordinates.
+    ///
+    /// ## Panics
+    /// if the coordinates are out of bounds
+    pub fn put_pixel(&mut self, x: u32, y: u32, px: [u8; 4]) {
+        let i = self.offset(x, y);
+        self.data[i..i + 4].copy_from_slice(&px);
+    }
+
+    /// Fill a rectangle, clipped to the image bounds.
+    pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, px: [u8; 4]) {
+        let x_end = x.saturating_add(w).min(self.width);
+        let y_end = y.saturating_add(h).min(self.height);
+        for py in y.min(y_end)..y_end {
+            for px_x in x.min(x_end)..x_end {
+                self.put_pixel(px_x, py, px);
+            }
+        }
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) out of bounds for {}x{} image",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+}
+
+/// Bytes of a `width`x`height` RGBA8 buffer, `None` if that overflows.
+fn byte_len(width: u32, height: u32) -> Option<usize> {
+    (width as usize)
+        .checked_mul(height as usize)?
+        .checked_mul(4)
+}
+
+/// Opaque RGBA pixel of a colour.
+pub fn rgba(c: Color) -> [u8; 4] {
+    let (r, g, b) = c.rgb;
+    [r, g, b, 255]
+}
+
+/// Resolve the foreground colour of a cell, mapping `None` and resets to [`DEFAULT_FG`].
+pub fn resolved_fg(c: &TerminalChar) -> Color {
+    match c.fg_color {
+        Some(col) if !col.reset => col,
+        _ => DEFAULT_FG,
+    }
+}
+
+/// Resolve the background colour of a cell, mapping `None` and resets to [`DEFAULT_BG`].
+pub fn resolved_bg(c: &TerminalChar) -> Color {
+    match c.bg_color {
+        Some(col) if !col.reset => col,
+        _ => DEFAULT_BG,
+    }
+}
+
+/// Minimal glyph renderer: fills the whole cell with the foreground colour
+/// for every non-space character.
+///
+/// Useful for previews and tests, real output wants a font rasterizer.
+pub fn block_glyph(cell_w: u32, cell_h: u32) -> impl Fn(&TerminalChar, &mut RgbaImage, u32, u32) {
+    move |c, img, x, y| {
+        if c.chr != ' ' {
+            img.fill_rect(x, y, cell_w, cell_h, rgba(resolved_fg(c)));
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// Render the sprite into an RGBA image with `cell_w × cell_h` pixels per cell.
+    ///
+    /// Every cell is first filled with its (resolved) background colour,
+    /// then `glyph` is called with the cell and the pixel coordinates of its
+    /// top-left corner to draw the character on top.
+    ///
+    /// ## Error
+    /// `InvalidInput` if a cell dimension is 0 or the image would be too large
+    pub fn render_rgba<F>(&self, cell_w: u32, cell_h: u32, glyph: F) -> io::Result<RgbaImage>
+    where
+        F: Fn(&TerminalChar, &mut RgbaImage, u32, u32),
+    {
+        if cell_w == 0 || cell_h == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cell dimensions must be non-zero",
+            ));
+        }
+        let size = (self.width as u32)
+            .checked_mul(cell_w)
+            .zip((self.height as u32).checked_mul(cell_h));
+        let Some((px_w, px_h)) = size else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "rendered image too large",
+            ));
+        };
+        let mut img = RgbaImage::try_new(px_w, px_h)?;
+        for (i, c) in self.pixels.iter().enumerate() {
+            let x = (i % self.width as usize) as u32 * cell_w;
+            let y = (i / self.width as usize) as u32 * cell_h;
+            img.fill_rect(x, y, cell_w, cell_h, rgba(resolved_bg(c)));
+            glyph(c, &mut img, x, y);
+        }
+        Ok(img)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_rgba_colours() {
+        let sprite = AsciiSprite::new(
+            2,
+            1,
+            vec![
+                TerminalChar::with_colors('#', Color::Red, Color::Blue),
+                TerminalChar::from_char(' '),
+            ],
+        )
+        .unwrap();
+
+        let img = sprite.render_rgba(2, 3, block_glyph(2, 3)).unwrap();
+        assert_eq!((img.width(), img.height()), (4, 3));
+        assert_eq!(img.get_pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 2), [255, 0, 0, 255]);
+        // space with no colours falls back to the default background
+        assert_eq!(img.get_pixel(2, 0), rgba(DEFAULT_BG));
+        assert!(sprite.render_rgba(0, 3, block_glyph(0, 3)).is_err());
+
+        let err = sprite
+            .render_rgba(u32::MAX, 1, block_glyph(1, 1))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(RgbaImage::try_new(u32::MAX, u32::MAX).is_err());
+        assert!(RgbaImage::from_raw(u32::MAX, u32::MAX, Vec::new()).is_none());
+    }
+}
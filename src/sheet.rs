@@ -0,0 +1,182 @@
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use byteorder::LittleEndian;
+
+use crate::{
+    AsciiSprite,
+    io::{self, Read, ReadBytesExt, Write, WriteBytesExt},
+};
+
+/// A collection of sprites that may all have different dimensions,
+/// e.g. the growing stages of an explosion.
+///
+/// ## File format
+/// ```text
+/// "ASCH"           magic
+/// u8               version
+/// u32              sprite count
+/// per sprite:
+///   u16 / u16      width / height
+///   characters     width * height, as `TerminalChar::write_to` writes them
+/// ```
+/// All integers are little-endian.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AsciiSpriteSheet {
+    pub sprites: Vec<AsciiSprite>,
+}
+
+impl AsciiSpriteSheet {
+    /// Magic bytes every sprite sheet file starts with.
+    pub const MAGIC: [u8; 4] = *b"ASCH";
+    /// Format version written and read by this library.
+    pub const VERSION: u8 = 1;
+    const MAX_SPRITES: usize = 100_000;
+
+    pub fn new(sprites: Vec<AsciiSprite>) -> Self {
+        Self { sprites }
+    }
+
+    /// The sprite at `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<&AsciiSprite> {
+        self.sprites.get(index)
+    }
+
+    pub fn push(&mut self, sprite: AsciiSprite) {
+        self.sprites.push(sprite);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let f = File::create(path)?;
+        let mut w = BufWriter::new(f);
+        self.write_to(&mut w)?;
+        w.flush()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let f = File::open(path)?;
+        let mut r = BufReader::new(f);
+        Self::read_from(&mut r)
+    }
+
+    /// Serialise the header, then every sprite with its dimensions.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.sprites.len() > Self::MAX_SPRITES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "too many sprites: {} (max {})",
+                    self.sprites.len(),
+                    Self::MAX_SPRITES
+                ),
+            ));
+        }
+        w.write_all(&Self::MAGIC)?;
+        w.write_u8(Self::VERSION)?;
+        w.write_u32::<LittleEndian>(self.sprites.len() as u32)?;
+        for s in &self.sprites {
+            w.write_u16::<LittleEndian>(s.width)?;
+            w.write_u16::<LittleEndian>(s.height)?;
+            s.write_to(w)?;
+        }
+        Ok(())
+    }
+
+    /// Deserialise a sprite sheet written by [`AsciiSpriteSheet::write_to`]
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if magic != Self::MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "bad magic number",
+            ));
+        }
+        let ver = r.read_u8()?;
+        if ver != Self::VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported sprite sheet version {}", ver),
+            ));
+        }
+
+        let count = r.read_u32::<LittleEndian>()? as usize;
+        if count > Self::MAX_SPRITES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("too many sprites: {} (max {})", count, Self::MAX_SPRITES),
+            ));
+        }
+
+        let mut sprites = Vec::with_capacity(count);
+        for i in 0..count {
+            let width = r.read_u16::<LittleEndian>()?;
+            let height = r.read_u16::<LittleEndian>()?;
+            if width > 4096 || height > 4096 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("sprite {} dimensions out of range, max 4096x4096", i),
+                ));
+            }
+            sprites.push(AsciiSprite::read_from(r, width, height)?);
+        }
+        Ok(Self { sprites })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{Color, TerminalChar};
+
+    #[test]
+    fn test_sprite_sheet_roundtrip() {
+        let sheet = AsciiSpriteSheet::new(
+            (1..=3u16)
+                .map(|n| {
+                    let c = TerminalChar::with_fg('*', Color::rgb(n as u8 * 80, 0, 0));
+                    AsciiSprite::new(n, n + 1, vec![c; (n * (n + 1)) as usize]).unwrap()
+                })
+                .collect(),
+        );
+
+        let path = "test_sprite_sheet.bin";
+        sheet.write_to_file(path).unwrap();
+        let loaded = AsciiSpriteSheet::read_from_file(path);
+        std::fs::remove_file(path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, sheet);
+        assert_eq!(loaded.get(2).map(|s| (s.width, s.height)), Some((3, 4)));
+        assert!(loaded.get(3).is_none());
+
+        let mut bytes = Vec::new();
+        sheet.write_to(&mut bytes).unwrap();
+        bytes[..4].copy_from_slice(b"ASCV");
+        assert!(AsciiSpriteSheet::read_from(&mut bytes.as_slice()).is_err());
+
+        // a header claiming one huge sprite, without its cells
+        let mut forged = AsciiSpriteSheet::MAGIC.to_vec();
+        forged.push(AsciiSpriteSheet::VERSION);
+        forged.extend_from_slice(&1u32.to_le_bytes());
+        forged.extend_from_slice(&4096u16.to_le_bytes());
+        forged.extend_from_slice(&4096u16.to_le_bytes());
+        let err = AsciiSpriteSheet::read_from(&mut forged.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
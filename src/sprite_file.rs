@@ -0,0 +1,218 @@
+//! Single sprite files (`.ascs`) and loading a video from a directory of them.
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use byteorder::LittleEndian;
+
+use crate::{
+    AsciiSprite, AsciiVideo, CharEncoding, TerminalChar, frame_period,
+    io::{self, Read, ReadBytesExt, Write, WriteBytesExt},
+    with_path,
+};
+
+impl AsciiSprite {
+    /// Magic bytes every single sprite file starts with.
+    pub const FILE_MAGIC: [u8; 4] = *b"ASCS";
+    /// Sprite file version written and read by this library.
+    pub const FILE_VERSION: u8 = 1;
+
+    /// Write the sprite to its own file, conventionally named `*.ascs`.
+    ///
+    /// ## File format
+    /// ```text
+    /// "ASCS"           magic
+    /// u8               version
+    /// u16 / u16        width / height
+    /// characters       width * height, as `TerminalChar::write_to` writes them
+    /// ```
+    /// All integers are little-endian.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(&Self::FILE_MAGIC)?;
+        w.write_u8(Self::FILE_VERSION)?;
+        w.write_u16::<LittleEndian>(self.width)?;
+        w.write_u16::<LittleEndian>(self.height)?;
+        self.write_to(&mut w)?;
+        w.flush()
+    }
+
+    /// Read a sprite file written by [`AsciiSprite::write_to_file`].
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut r = BufReader::new(file);
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if magic != Self::FILE_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "bad magic number",
+            ));
+        }
+        let ver = r.read_u8()?;
+        if ver != Self::FILE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported sprite file version {}", ver),
+            ));
+        }
+        let width = r.read_u16::<LittleEndian>()?;
+        let height = r.read_u16::<LittleEndian>()?;
+        if width > 4096 || height > 4096 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "sprite dimensions out of range, max 4096x4096",
+            ));
+        }
+        // check before reading, since the size alone can't be trusted
+        let available = len.saturating_sub(9);
+        let min_cell = TerminalChar::default().serialized_size(CharEncoding::Fixed) as u64;
+        if width as u64 * height as u64 * min_cell > available {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "sprite file declares {}x{} characters, more than {} bytes can hold",
+                    width, height, available
+                ),
+            ));
+        }
+        Self::read_from(&mut r, width, height)
+    }
+}
+
+impl AsciiVideo {
+    /// Build a video from the sprite files `{prefix}_{n}.ascs` in `dir`,
+    /// one frame per file, ordered by the number `n`, so `f_2` comes
+    /// before `f_10`. Other files are ignored.
+    ///
+    /// The video plays at `frame_rate` frames per second.
+    ///
+    /// ## Error
+    /// if `frame_rate` isn't a positive number, no file matches, or a file
+    /// can't be read or has another size than the first one. Errors about
+    /// a file name its path.
+    pub fn from_dir(
+        dir: impl AsRef<Path>,
+        prefix: &str,
+        frame_rate: f32,
+    ) -> io::Result<AsciiVideo> {
+        if frame_period(frame_rate).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid frame rate {}", frame_rate),
+            ));
+        }
+
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let number = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(prefix)?.strip_prefix('_'))
+                .and_then(|rest| rest.strip_suffix(".ascs"))
+                .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|n| n.parse::<u64>().ok());
+            if let Some(n) = number {
+                files.push((n, path));
+            }
+        }
+        if files.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no {}_*.ascs files in {}", prefix, dir.display()),
+            ));
+        }
+        files.sort();
+
+        let mut frames: Vec<AsciiSprite> = Vec::with_capacity(files.len());
+        for (_, path) in &files {
+            let frame = AsciiSprite::read_from_file(path).map_err(|e| with_path(e, path))?;
+            if let Some(first) = frames.first()
+                && (frame.width, frame.height) != (first.width, first.height)
+            {
+                return Err(with_path(
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "sprite has size {}x{} but expected {}x{}",
+                            frame.width, frame.height, first.width, first.height
+                        ),
+                    ),
+                    path,
+                ));
+            }
+            frames.push(frame);
+        }
+
+        let (width, height) = (frames[0].width, frames[0].height);
+        let mut video = AsciiVideo::new(width, height, frames)?;
+        video.frame_rate = Some(frame_rate);
+        Ok(video)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_read_from_file_truncated() {
+        let path = "test_truncated_sprite.ascs";
+        let mut bytes = AsciiSprite::FILE_MAGIC.to_vec();
+        bytes.push(AsciiSprite::FILE_VERSION);
+        bytes.extend_from_slice(&4096u16.to_le_bytes());
+        bytes.extend_from_slice(&4096u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        fs::write(path, &bytes).unwrap();
+        let err = AsciiSprite::read_from_file(path).unwrap_err();
+        fs::remove_file(path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("4096x4096"));
+    }
+
+    #[test]
+    fn test_from_dir() {
+        let dir = "test_from_dir";
+        fs::create_dir_all(dir).unwrap();
+        let frame = |c: char| {
+            AsciiSprite::new(2, 1, vec![TerminalChar::with_fg(c, Color::Teal); 2]).unwrap()
+        };
+        for (n, c) in [(10, 'c'), (2, 'b'), (1, 'a')] {
+            frame(c)
+                .write_to_file(Path::new(dir).join(format!("walk_{}.ascs", n)))
+                .unwrap();
+        }
+        frame('x')
+            .write_to_file(Path::new(dir).join("run_1.ascs"))
+            .unwrap();
+        fs::write(format!("{}/walk_notes.txt", dir), "ignored").unwrap();
+
+        let video = AsciiVideo::from_dir(dir, "walk", 12.0);
+        let none = AsciiVideo::from_dir(dir, "jump", 12.0);
+        let bad_rate = AsciiVideo::from_dir(dir, "walk", 0.0);
+        AsciiSprite::new(1, 1, vec![TerminalChar::from('d')])
+            .unwrap()
+            .write_to_file(Path::new(dir).join("walk_11.ascs"))
+            .unwrap();
+        let mismatch = AsciiVideo::from_dir(dir, "walk", 12.0);
+        fs::remove_dir_all(dir).unwrap();
+
+        let video = video.unwrap();
+        let chars: Vec<_> = video.frames.iter().map(|f| f.to_plain_string()).collect();
+        assert_eq!(chars, ["aa", "bb", "cc"]);
+        assert_eq!(video.frames[0], frame('a'));
+        assert_eq!(video.frame_rate, Some(12.0));
+        assert_eq!(none.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(bad_rate.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let err = mismatch.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("walk_11.ascs"));
+    }
+}
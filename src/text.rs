@@ -0,0 +1,94 @@
+//! Laying out plain text in sprites.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{AsciiSprite, Color, TerminalChar};
+
+/// Greedily wrap one paragraph into lines of at most `width` characters.
+/// Words longer than `width` are broken into `width`-sized pieces.
+fn wrap_paragraph(paragraph: &str, width: usize, lines: &mut Vec<Vec<char>>) {
+    let mut line: Vec<char> = Vec::new();
+    for word in paragraph.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        if !line.is_empty() && line.len() + 1 + word.len() <= width {
+            line.push(' ');
+            line.append(&mut word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(core::mem::take(&mut line));
+        }
+        while word.len() > width {
+            let rest = word.split_off(width);
+            lines.push(core::mem::replace(&mut word, rest));
+        }
+        line = word;
+    }
+    lines.push(line);
+}
+
+impl AsciiSprite {
+    /// Lay out `text` in a sprite `width` columns wide, as tall as needed.
+    ///
+    /// Lines are wrapped at whitespace, runs of whitespace collapse into a
+    /// single space and words longer than `width` are hard-broken. Newlines
+    /// start a new line. Each character takes one cell, coloured `fg`, and
+    /// the rest of every line is padded with spaces.
+    ///
+    /// A `width` of 0 gives an empty sprite, lines past the 65535th are dropped.
+    pub fn from_wrapped_text(text: &str, width: u16, fg: Option<Color>) -> AsciiSprite {
+        if width == 0 {
+            return AsciiSprite {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            };
+        }
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            wrap_paragraph(paragraph, width as usize, &mut lines);
+        }
+        lines.truncate(u16::MAX as usize);
+
+        let blank = TerminalChar::default();
+        let mut pixels = vec![blank; lines.len() * width as usize];
+        for (row, line) in pixels.chunks_mut(width as usize).zip(&lines) {
+            for (cell, &chr) in row.iter_mut().zip(line) {
+                *cell = TerminalChar {
+                    chr,
+                    fg_color: fg,
+                    bg_color: None,
+                };
+            }
+        }
+        AsciiSprite {
+            width,
+            height: lines.len() as u16,
+            pixels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapped_text() {
+        let sprite =
+            AsciiSprite::from_wrapped_text("the quick  brown fox\n\nsupercalifragilistic", 8, None);
+        assert_eq!(
+            sprite.to_plain_string(),
+            [
+                "the     ", "quick   ", "brown   ", "fox     ", "        ", "supercal", "ifragili",
+                "stic    "
+            ]
+            .join("\n")
+        );
+
+        let sprite = AsciiSprite::from_wrapped_text("hi there", 10, Some(Color::Red));
+        assert_eq!(sprite.to_plain_string(), ["hi there  "].join("\n"));
+        assert_eq!(sprite.get_char(0, 0).unwrap().fg_color, Some(Color::Red));
+        assert_eq!(sprite.get_char(9, 0).unwrap(), TerminalChar::default());
+    }
+}
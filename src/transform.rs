@@ -0,0 +1,316 @@
+//! Sprite transforms: resizing, tiling and compositing.
+
+use alloc::{format, vec};
+
+use crate::{AsciiSprite, Color, TerminalChar, io};
+
+/// Where content is placed inside a larger area, or which part of it is kept
+/// in a smaller one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Offset of content of size `inner` inside `outer` along each axis;
+    /// negative if the content is the larger one.
+    fn offset(self, inner: (u16, u16), outer: (u16, u16)) -> (i32, i32) {
+        let (h, v) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        };
+        let dx = (outer.0 as i32 - inner.0 as i32) * h / 2;
+        let dy = (outer.1 as i32 - inner.1 as i32) * v / 2;
+        (dx, dy)
+    }
+}
+
+/// How [`AsciiSprite::overlay_with`] combines a source cell with the cell below.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// The source cell replaces the destination.
+    #[default]
+    Replace,
+    /// Only the source's colours are copied, the destination keeps its character.
+    KeepChar,
+    /// The destination colours are multiplied with the source's, see [`Color::multiply`].
+    ColorMultiply,
+    /// The source only shows where the destination is blank.
+    Behind,
+}
+
+/// What [`AsciiSprite::overlay_wrapping`] does with source cells that land
+/// outside the destination.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WrapMode {
+    /// They are dropped.
+    #[default]
+    Clip,
+    /// They wrap around to the opposite edge, like on a torus.
+    Wrap,
+}
+
+impl BlendMode {
+    fn apply(self, dst: &mut TerminalChar, src: TerminalChar) {
+        let multiply = |d: Option<Color>, s: Option<Color>| match (d, s) {
+            (Some(d), Some(s)) => Some(d.multiply(s)),
+            (d, _) => d,
+        };
+        match self {
+            BlendMode::Replace => *dst = src,
+            BlendMode::KeepChar => {
+                dst.fg_color = src.fg_color.or(dst.fg_color);
+                dst.bg_color = src.bg_color.or(dst.bg_color);
+            }
+            BlendMode::ColorMultiply => {
+                dst.fg_color = multiply(dst.fg_color, src.fg_color);
+                dst.bg_color = multiply(dst.bg_color, src.bg_color);
+            }
+            BlendMode::Behind => {
+                if dst.is_blank() {
+                    *dst = src;
+                }
+            }
+        }
+    }
+}
+
+impl AsciiSprite {
+    /// Draw `other` on top of this sprite with its top left corner at `(x, y)`,
+    /// see [`AsciiSprite::overlay_with`].
+    pub fn overlay(&mut self, other: &AsciiSprite, x: i32, y: i32) {
+        self.overlay_with(other, x, y, BlendMode::Replace);
+    }
+
+    /// Combine `other` with this sprite, its top left corner at `(x, y)`.
+    ///
+    /// Blank cells of `other` (a space without colours) are transparent,
+    /// the rest is combined with the cells below according to `mode`.
+    /// Whatever lies outside this sprite is clipped.
+    pub fn overlay_with(&mut self, other: &AsciiSprite, x: i32, y: i32, mode: BlendMode) {
+        self.overlay_wrapping(other, x, y, mode, WrapMode::Clip);
+    }
+
+    /// Like [`AsciiSprite::overlay_with`], with `wrap` deciding what happens
+    /// to the part of `other` outside this sprite.
+    ///
+    /// With [`WrapMode::Wrap`] the offset may be anything: it is taken
+    /// modulo the size of this sprite, so animating `x` or `y` scrolls a
+    /// pattern seamlessly. Where `other` is larger than this sprite, its
+    /// later cells are drawn over the earlier ones that wrapped to the same
+    /// place.
+    pub fn overlay_wrapping(
+        &mut self,
+        other: &AsciiSprite,
+        x: i32,
+        y: i32,
+        mode: BlendMode,
+        wrap: WrapMode,
+    ) {
+        let (w, h) = (self.width as i64, self.height as i64);
+        if w == 0 || h == 0 {
+            return;
+        }
+        for (ox, oy, c) in other.enumerate_cells() {
+            if c.is_blank() {
+                continue;
+            }
+            let (mut nx, mut ny) = (ox as i64 + x as i64, oy as i64 + y as i64);
+            if wrap == WrapMode::Wrap {
+                (nx, ny) = (nx.rem_euclid(w), ny.rem_euclid(h));
+            }
+            if let (Ok(nx), Ok(ny)) = (u16::try_from(nx), u16::try_from(ny))
+                && let Some(idx) = self.index_of(nx, ny)
+            {
+                mode.apply(&mut self.pixels[idx], *c);
+            }
+        }
+    }
+
+    /// Return a `new_w`x`new_h` copy of the sprite, placed according to
+    /// `anchor`. New cells are set to `fill`, cells that don't fit are cropped.
+    pub fn resize(
+        &self,
+        new_w: u16,
+        new_h: u16,
+        fill: TerminalChar,
+        anchor: Anchor,
+    ) -> AsciiSprite {
+        let (dx, dy) = anchor.offset((self.width, self.height), (new_w, new_h));
+        let mut out = AsciiSprite {
+            width: new_w,
+            height: new_h,
+            pixels: vec![fill; new_w as usize * new_h as usize],
+        };
+        for (x, y, c) in self.enumerate_cells() {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if let (Ok(nx), Ok(ny)) = (u16::try_from(nx), u16::try_from(ny)) {
+                out.set_char(nx, ny, *c);
+            }
+        }
+        out
+    }
+
+    /// Repeat the sprite to fill an `out_w`x`out_h` area, starting with its
+    /// top left corner and cut off at the right and bottom edges.
+    ///
+    /// ## Error
+    /// if the sprite is empty
+    pub fn tile(&self, out_w: u16, out_h: u16) -> io::Result<AsciiSprite> {
+        if self.width == 0 || self.height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't tile an empty {}x{} sprite", self.width, self.height),
+            ));
+        }
+        let pixels = (0..out_h)
+            .flat_map(|y| {
+                let row = (y % self.height) as usize * self.width as usize;
+                (0..out_w).map(move |x| self.pixels[row + (x % self.width) as usize])
+            })
+            .collect();
+        AsciiSprite::new(out_w, out_h, pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_anchors() {
+        let art = ["ab", "cd"];
+        let sprite = AsciiSprite::from_rows(art.into_iter().map(Into::into).collect()).unwrap();
+        let dot = TerminalChar::from('.');
+
+        assert_eq!(
+            sprite.resize(4, 3, dot, Anchor::TopLeft).to_plain_string(),
+            ["ab..", "cd..", "...."].join("\n")
+        );
+        assert_eq!(
+            sprite.resize(4, 4, dot, Anchor::Center).to_plain_string(),
+            ["....", ".ab.", ".cd.", "...."].join("\n")
+        );
+        assert_eq!(
+            sprite
+                .resize(3, 3, dot, Anchor::BottomRight)
+                .to_plain_string(),
+            ["...", ".ab", ".cd"].join("\n")
+        );
+        assert_eq!(
+            sprite
+                .resize(1, 1, dot, Anchor::BottomRight)
+                .to_plain_string(),
+            ["d"].join("\n")
+        );
+        assert_eq!(
+            sprite.resize(1, 2, dot, Anchor::Top).to_plain_string(),
+            ["a", "c"].join("\n")
+        );
+        assert_eq!(sprite.resize(2, 2, dot, Anchor::Center), sprite);
+    }
+
+    #[test]
+    fn test_tile() {
+        let sprite = AsciiSprite::from_rows(vec!["ab".into(), "cd".into()]).unwrap();
+        assert_eq!(
+            sprite.tile(5, 3).unwrap().to_plain_string(),
+            ["ababa", "cdcdc", "ababa"].join("\n")
+        );
+        assert_eq!(
+            sprite.tile(1, 1).unwrap().to_plain_string(),
+            ["a"].join("\n")
+        );
+        assert_eq!(sprite.tile(2, 2).unwrap(), sprite);
+        assert_eq!(sprite.tile(0, 4).unwrap().pixels.len(), 0);
+
+        let empty = AsciiSprite::new(0, 3, vec![]).unwrap();
+        assert_eq!(
+            empty.tile(2, 2).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_overlay_blend_modes() {
+        let base_cell = TerminalChar::with_colors('.', Color::White, Color::Grey);
+        let mut base = AsciiSprite::new(3, 1, vec![base_cell; 3]).unwrap();
+        base.pixels[2] = TerminalChar::default();
+        let top = AsciiSprite::new(
+            2,
+            1,
+            vec![
+                TerminalChar::with_colors('#', Color::Red, Color::Grey),
+                TerminalChar::default(),
+            ],
+        )
+        .unwrap();
+        let red_cell = top.pixels[0];
+
+        let mut s = base.clone();
+        s.overlay(&top, 0, 0);
+        assert_eq!(s.pixels[..2], [red_cell, base_cell]);
+
+        let mut s = base.clone();
+        s.overlay_with(&top, 1, 0, BlendMode::KeepChar);
+        assert_eq!(
+            s.pixels[1],
+            TerminalChar::with_colors('.', Color::Red, Color::Grey)
+        );
+
+        let mut s = base.clone();
+        s.overlay_with(&top, 0, 0, BlendMode::ColorMultiply);
+        let dimmed = Color::Grey.multiply(Color::Grey);
+        assert_eq!(
+            s.pixels[0],
+            TerminalChar::with_colors('.', Color::Red, dimmed)
+        );
+
+        let mut s = base.clone();
+        s.overlay_with(&top, 1, 0, BlendMode::Behind);
+        s.overlay_with(&top, 2, 0, BlendMode::Behind);
+        s.overlay_with(&top, -1, 0, BlendMode::Replace);
+        assert_eq!(s.pixels, [base_cell, base_cell, red_cell]);
+    }
+
+    #[test]
+    fn test_overlay_wrapping() {
+        let pattern =
+            AsciiSprite::new(2, 1, vec![TerminalChar::from('#'), TerminalChar::from('o')]).unwrap();
+        let base = AsciiSprite::new(3, 2, vec![TerminalChar::from('.'); 6]).unwrap();
+
+        let mut s = base.clone();
+        s.overlay_wrapping(&pattern, 2, 1, BlendMode::Replace, WrapMode::Wrap);
+        assert_eq!(s.to_plain_string(), "...\no.#");
+        let mut s = base.clone();
+        s.overlay_wrapping(&pattern, -7, -3, BlendMode::Replace, WrapMode::Wrap);
+        assert_eq!(s.to_plain_string(), "...\no.#");
+
+        let mut clipped = base.clone();
+        clipped.overlay_wrapping(&pattern, 2, 1, BlendMode::Replace, WrapMode::Clip);
+        let mut plain = base.clone();
+        plain.overlay(&pattern, 2, 1);
+        assert_eq!(clipped, plain);
+        assert_eq!(clipped.to_plain_string(), "...\n..#");
+
+        let mut empty = AsciiSprite::new(0, 0, vec![]).unwrap();
+        empty.overlay_wrapping(&pattern, 1, 1, BlendMode::Replace, WrapMode::Wrap);
+        assert!(empty.pixels.is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! Generated transitions between two sprites.
+
+use alloc::{format, vec::Vec};
+
+use crate::{AsciiSprite, AsciiVideo, Color, ReadLimits, TerminalChar, io, noise::SplitMix64};
+
+/// How [`AsciiVideo::transition`] changes one sprite into the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionKind {
+    /// Blend the colours step by step, see [`Color::blend`]. Characters and
+    /// colours that can't be mixed, unset or reset ones, switch halfway.
+    Fade,
+    /// Reveal the target column by column, from the right edge to the left.
+    WipeLeft,
+    /// Reveal the target column by column, from the left edge to the right.
+    WipeRight,
+    /// Reveal the target cell by cell in a random order. The same `seed`
+    /// gives the same order across runs and platforms.
+    Dissolve { seed: u64 },
+}
+
+impl AsciiVideo {
+    /// Generate `frames` frames going from `from` to `to`.
+    ///
+    /// `from` itself isn't part of the result: the first frame already
+    /// shows the first step, the last one is exactly `to`. The video has no
+    /// timing set, pick a frame rate to control the speed.
+    ///
+    /// ## Error
+    /// if `frames` is 0 or more than a reader accepts by default (see
+    /// [`ReadLimits`]), the sprites have different dimensions or one that
+    /// [`AsciiVideo::new`] rejects
+    pub fn transition(
+        from: &AsciiSprite,
+        to: &AsciiSprite,
+        frames: usize,
+        kind: TransitionKind,
+    ) -> io::Result<AsciiVideo> {
+        if (from.width, from.height) != (to.width, to.height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can't transition from a {}x{} to a {}x{} sprite",
+                    from.width, from.height, to.width, to.height
+                ),
+            ));
+        }
+        if frames == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a transition needs at least 1 frame",
+            ));
+        }
+        let max_frames = ReadLimits::default().max_frames;
+        if frames > max_frames {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "a transition of {} frames is longer than the maximum of {}",
+                    frames, max_frames
+                ),
+            ));
+        }
+        // `step * len / frames` for `step` in `1..=frames`, in u64 so that
+        // it can't overflow
+        let part = |step: usize, len: usize| (step as u64 * len as u64 / frames as u64) as usize;
+
+        let width = from.width as usize;
+        let order = match kind {
+            TransitionKind::Dissolve { seed } => shuffled(from.pixels.len(), seed),
+            _ => Vec::new(),
+        };
+        let steps = (1..=frames).map(|step| {
+            let mut frame = from.clone();
+            match kind {
+                TransitionKind::Fade => {
+                    let t = step as f32 / frames as f32;
+                    for (dst, src) in frame.pixels.iter_mut().zip(&to.pixels) {
+                        *dst = fade(*dst, *src, t);
+                    }
+                }
+                TransitionKind::WipeLeft | TransitionKind::WipeRight => {
+                    let cols = part(step, width);
+                    let range = match kind {
+                        TransitionKind::WipeRight => 0..cols,
+                        _ => width - cols..width,
+                    };
+                    for (dst, src) in frame
+                        .pixels
+                        .chunks_exact_mut(width.max(1))
+                        .zip(to.pixels.chunks_exact(width.max(1)))
+                    {
+                        dst[range.clone()].copy_from_slice(&src[range.clone()]);
+                    }
+                }
+                TransitionKind::Dissolve { .. } => {
+                    for &i in &order[..part(step, order.len())] {
+                        frame.pixels[i] = to.pixels[i];
+                    }
+                }
+            }
+            frame
+        });
+        AsciiVideo::new(from.width, from.height, steps.collect())
+    }
+}
+
+/// The cell `t` of the way from `a` to `b`.
+fn fade(a: TerminalChar, b: TerminalChar, t: f32) -> TerminalChar {
+    let late = t >= 0.5;
+    let mix = |a: Option<Color>, b: Option<Color>| match (a, b) {
+        (Some(a), Some(b)) if !a.reset && !b.reset => Some(a.blend(b, t)),
+        _ if late => b,
+        _ => a,
+    };
+    TerminalChar {
+        chr: if late { b.chr } else { a.chr },
+        fg_color: mix(a.fg_color, b.fg_color),
+        bg_color: mix(a.bg_color, b.bg_color),
+    }
+}
+
+/// The indices `0..len` in a random order determined by `seed`.
+fn shuffled(len: usize, seed: u64) -> Vec<usize> {
+    let mut rng = SplitMix64::new(seed);
+    let mut order: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        order.swap(i, rng.below(i + 1));
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec};
+
+    fn sprite(text: &str, col: Color) -> AsciiSprite {
+        let pixels = text
+            .chars()
+            .map(|c| TerminalChar::with_fg(c, col))
+            .collect();
+        AsciiSprite::new(text.len() as u16 / 2, 2, pixels).unwrap()
+    }
+
+    fn chars(video: &AsciiVideo) -> Vec<String> {
+        video.frames.iter().map(|f| f.to_plain_string()).collect()
+    }
+
+    #[test]
+    fn test_wipes() {
+        let (a, b) = (sprite("aaaaaa", Color::Red), sprite("bbbbbb", Color::Navy));
+        let video = AsciiVideo::transition(&a, &b, 3, TransitionKind::WipeRight).unwrap();
+        assert_eq!(chars(&video), ["baa\nbaa", "bba\nbba", "bbb\nbbb"]);
+        assert_eq!(video.frames[2], b);
+        let video = AsciiVideo::transition(&a, &b, 2, TransitionKind::WipeLeft).unwrap();
+        assert_eq!(chars(&video), ["aab\naab", "bbb\nbbb"]);
+
+        let err = AsciiVideo::transition(&a, &sprite("bb", Color::Red), 2, TransitionKind::Fade);
+        assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(AsciiVideo::transition(&a, &b, 0, TransitionKind::Fade).is_err());
+        let err = AsciiVideo::transition(&a, &b, usize::MAX, TransitionKind::WipeLeft);
+        assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_fade() {
+        let a = sprite("aa", Color::rgb(0, 0, 0));
+        let mut b = sprite("bb", Color::rgb(200, 100, 0));
+        b.pixels[1].fg_color = None;
+        let video = AsciiVideo::transition(&a, &b, 4, TransitionKind::Fade).unwrap();
+        let fg: Vec<_> = video.frames.iter().map(|f| f.pixels[0].fg_color).collect();
+        assert_eq!(
+            fg,
+            [
+                Some(Color::rgb(50, 25, 0)),
+                Some(Color::rgb(100, 50, 0)),
+                Some(Color::rgb(150, 75, 0)),
+                Some(Color::rgb(200, 100, 0)),
+            ]
+        );
+        assert_eq!(chars(&video), ["a\na", "b\nb", "b\nb", "b\nb"]);
+        assert_eq!(video.frames[0].pixels[1].fg_color, a.pixels[1].fg_color);
+        assert_eq!(video.frames[1].pixels[1].fg_color, None);
+    }
+
+    #[test]
+    fn test_dissolve() {
+        let a = AsciiSprite::new(4, 4, vec![TerminalChar::from('a'); 16]).unwrap();
+        let b = AsciiSprite::new(4, 4, vec![TerminalChar::from('b'); 16]).unwrap();
+        let kind = TransitionKind::Dissolve { seed: 7 };
+        let video = AsciiVideo::transition(&a, &b, 4, kind).unwrap();
+        let revealed: Vec<_> = video
+            .frames
+            .iter()
+            .map(|f| f.pixels.iter().filter(|p| p.chr == 'b').count())
+            .collect();
+        assert_eq!(revealed, [4, 8, 12, 16]);
+        // revealed cells stay revealed
+        for pair in video.frames.windows(2) {
+            assert!(
+                pair[0]
+                    .pixels
+                    .iter()
+                    .zip(&pair[1].pixels)
+                    .all(|(p, n)| p.chr == 'a' || n.chr == 'b')
+            );
+        }
+
+        assert_eq!(AsciiVideo::transition(&a, &b, 4, kind).unwrap(), video);
+        let other = AsciiVideo::transition(&a, &b, 4, TransitionKind::Dissolve { seed: 8 });
+        assert_ne!(other.unwrap(), video);
+    }
+}
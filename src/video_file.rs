@@ -0,0 +1,177 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{AsciiSprite, AsciiVideo, CharEncoding, FormatInfo, ReadLimits, cell_count, codec};
+
+/// An open video file serving individual frames on demand.
+///
+/// Frames are stored with variable-size cells, so [`AsciiVideoFile::open`]
+/// scans the file once to record where every frame starts. After that,
+/// [`AsciiVideoFile::frame`] is one seek and one read of exactly that frame,
+/// independent of the file size, which keeps scrubbing through long
+/// recordings fast. Only the frame index is held in memory.
+///
+/// Gzip compressed files can't be accessed this way.
+#[derive(Debug)]
+pub struct AsciiVideoFile {
+    file: File,
+    info: FormatInfo,
+    metadata: BTreeMap<String, String>,
+    char_encoding: CharEncoding,
+    /// Start of every frame, followed by the end of the last one.
+    offsets: Vec<u64>,
+}
+
+impl AsciiVideoFile {
+    /// Open a video file and index its frames.
+    ///
+    /// ## Error
+    /// if the header is invalid or the file ends before the last frame does
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let f = File::open(path)?;
+        let len = f.metadata()?.len();
+        let mut r = BufReader::new(f);
+        let (info, ext) = AsciiVideo::read_header(&mut r, ReadLimits::default())?;
+        AsciiVideo::check_declared_size(&info, ext.char_encoding, len)?;
+        let cells = cell_count(info.width, info.height, io::ErrorKind::InvalidData)?;
+
+        let mut pos = r.stream_position()?;
+        let mut offsets = Vec::with_capacity(info.frame_count + 1);
+        offsets.push(pos);
+        for _ in 0..info.frame_count {
+            for _ in 0..cells {
+                pos += skip_char(&mut r, ext.char_encoding)?;
+            }
+            offsets.push(pos);
+        }
+
+        Ok(Self {
+            file: r.into_inner(),
+            info,
+            metadata: ext.metadata,
+            char_encoding: ext.char_encoding,
+            offsets,
+        })
+    }
+
+    /// Header of the file.
+    pub fn info(&self) -> &FormatInfo {
+        &self.info
+    }
+
+    /// Key/value metadata stored in the header.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Number of frames in the file.
+    pub fn frame_count(&self) -> usize {
+        self.info.frame_count
+    }
+
+    /// Read and decode the frame at `index`.
+    ///
+    /// ## Error
+    /// if `index` is out of range or the file changed since it was opened
+    pub fn frame(&mut self, index: usize) -> io::Result<AsciiSprite> {
+        if index >= self.info.frame_count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame {} out of bounds for video with {} frames",
+                    index, self.info.frame_count
+                ),
+            ));
+        }
+        let (start, end) = (self.offsets[index], self.offsets[index + 1]);
+        let mut buf = vec![0; (end - start) as usize];
+        self.file.seek(SeekFrom::Start(start))?;
+        self.file.read_exact(&mut buf)?;
+        AsciiSprite::read_encoded(
+            &mut buf.as_slice(),
+            self.info.width,
+            self.info.height,
+            self.char_encoding,
+        )
+    }
+}
+
+/// Skip over one encoded character, returning its length in bytes.
+fn skip_char<R: Read>(r: &mut R, encoding: CharEncoding) -> io::Result<u64> {
+    let mut buf = [0u8; 4];
+    let mut len = match encoding {
+        CharEncoding::Fixed => {
+            r.read_exact(&mut buf)?;
+            4
+        }
+        CharEncoding::Varint => {
+            let mut n = 1;
+            r.read_exact(&mut buf[..1])?;
+            while buf[0] & 0x80 != 0 {
+                if n == 5 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "code point varint too long",
+                    ));
+                }
+                r.read_exact(&mut buf[..1])?;
+                n += 1;
+            }
+            n
+        }
+    };
+    for _ in 0..2 {
+        r.read_exact(&mut buf[..1])?;
+        let rest = codec::color_len(buf[0]) - 1;
+        r.read_exact(&mut buf[..rest])?;
+        len += 1 + rest as u64;
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, TerminalChar};
+
+    #[test]
+    fn test_random_frame_access() {
+        let frames: Vec<_> = (0..10u8)
+            .map(|i| {
+                let mut pixels = vec![TerminalChar::from('.'); 6];
+                pixels[i as usize % 6] =
+                    TerminalChar::with_colors('#', Color::rgb(i, 0, 0), Color::Navy);
+                AsciiSprite::new(3, 2, pixels).unwrap()
+            })
+            .collect();
+        let mut video = AsciiVideo::new(3, 2, frames.clone()).unwrap();
+        video.set_metadata("title", "scrub");
+        video.char_encoding = CharEncoding::Varint;
+
+        let path = "test_video_file.bin";
+        video.write_to_file(path).unwrap();
+        let mut file = AsciiVideoFile::open(path).unwrap();
+        assert_eq!(file.frame_count(), 10);
+        assert_eq!(
+            file.metadata().get("title").map(String::as_str),
+            Some("scrub")
+        );
+        let read = [7, 0, 9, 3].map(|i| file.frame(i));
+        let past_end = file.frame(10);
+        drop(file);
+        let bytes = std::fs::read(path).unwrap();
+        std::fs::write(path, &bytes[..bytes.len() - 1]).unwrap();
+        let truncated = AsciiVideoFile::open(path);
+        std::fs::remove_file(path).unwrap();
+
+        for (frame, i) in read.into_iter().zip([7, 0, 9, 3]) {
+            assert_eq!(frame.unwrap(), frames[i]);
+        }
+        assert_eq!(past_end.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(truncated.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::{AsciiSprite, AsciiVideo, CharEncoding};
+
+/// Writes a video file frame by frame, for recordings whose length isn't
+/// known up front.
+///
+/// Every frame goes to the file as soon as it is pushed, so memory use
+/// doesn't grow with the recording. The frame count in the header is only
+/// filled in by [`AsciiVideoWriter::finish`]; a file that was never
+/// finished reads as a video without frames.
+///
+/// Colours from [`crate::Color::ansi256_exact`] are stored as their RGB
+/// value, as the header is written before any frame is known.
+#[derive(Debug)]
+pub struct AsciiVideoWriter {
+    w: BufWriter<File>,
+    width: u16,
+    height: u16,
+    frame_count: u32,
+}
+
+impl AsciiVideoWriter {
+    /// Create the file at `path`, replacing an existing one, and write the
+    /// header of a `width` by `height` video.
+    ///
+    /// ## Error
+    /// if a dimension is out of the range [`AsciiVideo::new`] accepts, before
+    /// the file is created, or if writing fails
+    pub fn create(path: impl AsRef<Path>, width: u16, height: u16) -> io::Result<Self> {
+        AsciiVideo::check_dimensions(width, height)?;
+        let mut w = BufWriter::new(File::create(path)?);
+        AsciiVideo::write_header(&mut w, width, height, 0, &[])?;
+        Ok(Self {
+            w,
+            width,
+            height,
+            frame_count: 0,
+        })
+    }
+
+    /// Number of frames pushed so far.
+    pub fn frame_count(&self) -> usize {
+        self.frame_count as usize
+    }
+
+    /// Append a frame to the file.
+    ///
+    /// ## Error
+    /// if the frame doesn't have the size of the video, the file already
+    /// holds `u32::MAX` frames, or writing fails
+    pub fn push_frame(&mut self, frame: &AsciiSprite) -> io::Result<()> {
+        if frame.width != self.width || frame.height != self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Frame {} has size {}x{} but expected {}x{}",
+                    self.frame_count, frame.width, frame.height, self.width, self.height
+                ),
+            ));
+        }
+        let count = self.frame_count.checked_add(1).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "too many frames for one file")
+        })?;
+        frame.write_encoded(&mut self.w, CharEncoding::Fixed, false)?;
+        self.frame_count = count;
+        Ok(())
+    }
+
+    /// Write the final frame count into the header and flush the file.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.w.flush()?;
+        let f = self.w.get_mut();
+        f.seek(SeekFrom::Start(AsciiVideo::FRAME_COUNT_OFFSET))?;
+        f.write_all(&self.frame_count.to_le_bytes())?;
+        f.flush()
+    }
+}
+
+impl AsciiVideo {
+    /// Write a video file from frames produced one at a time, without ever
+    /// holding more than one of them.
+    ///
+    /// The header declares `frame_count` frames, which `frames` has to yield
+    /// exactly. Unlike [`AsciiVideoWriter`] this never seeks, but the count
+    /// has to be known up front. If anything fails the file is left
+    /// incomplete. Like with [`AsciiVideoWriter`], exact ANSI-256 colours
+    /// are stored as their RGB value.
+    ///
+    /// ## Error
+    /// if a dimension is out of the range [`AsciiVideo::new`] accepts, before
+    /// the file is created, if `frames` yields an error, a frame of another
+    /// size, or not exactly `frame_count` frames, or if writing fails
+    pub fn write_frames_from_iter<I>(
+        path: impl AsRef<Path>,
+        width: u16,
+        height: u16,
+        frame_count: usize,
+        frames: I,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = io::Result<AsciiSprite>>,
+    {
+        AsciiVideo::check_dimensions(width, height)?;
+        let count = u32::try_from(frame_count).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "too many frames for one file")
+        })?;
+        let mut w = BufWriter::new(File::create(path)?);
+        AsciiVideo::write_header(&mut w, width, height, count, &[])?;
+
+        let mut frames = frames.into_iter();
+        for i in 0..frame_count {
+            let frame = frames.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("expected {} frames but got {}", frame_count, i),
+                )
+            })??;
+            if frame.width != width || frame.height != height {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Frame {} has size {}x{} but expected {}x{}",
+                        i, frame.width, frame.height, width, height
+                    ),
+                ));
+            }
+            frame.write_encoded(&mut w, CharEncoding::Fixed, false)?;
+        }
+        if frames.next().is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected {} frames but got more", frame_count),
+            ));
+        }
+        w.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, TerminalChar};
+
+    #[test]
+    fn test_streaming_writer() {
+        let frames: Vec<_> = (0..5u8)
+            .map(|i| {
+                let mut pixels = vec![TerminalChar::from('.'); 6];
+                pixels[i as usize] = TerminalChar::with_fg('#', Color::rgb(i, 0, 0));
+                AsciiSprite::new(3, 2, pixels).unwrap()
+            })
+            .collect();
+
+        let path = "test_streaming_writer.bin";
+        let err = AsciiVideoWriter::create(path, 0, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(AsciiVideoWriter::create(path, 3, 5000).is_err());
+        assert!(!Path::new(path).exists());
+        let mut writer = AsciiVideoWriter::create(path, 3, 2).unwrap();
+        for frame in &frames {
+            writer.push_frame(frame).unwrap();
+        }
+        let wrong =
+            writer.push_frame(&AsciiSprite::new(2, 2, vec![TerminalChar::default(); 4]).unwrap());
+        assert_eq!(writer.frame_count(), 5);
+        writer.finish().unwrap();
+        let read = AsciiVideo::read_from_file(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(wrong.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(read.unwrap(), AsciiVideo::new(3, 2, frames).unwrap());
+
+        let exact = TerminalChar::with_fg('x', Color::ansi256_exact(1));
+        let mut writer = AsciiVideoWriter::create(path, 1, 1).unwrap();
+        writer
+            .push_frame(&AsciiSprite::new(1, 1, vec![exact]).unwrap())
+            .unwrap();
+        writer.finish().unwrap();
+        let read = AsciiVideo::read_from_file(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            read.unwrap().frames[0].pixels[0],
+            TerminalChar::with_fg('x', Color::Maroon)
+        );
+    }
+
+    #[test]
+    fn test_write_frames_from_iter() {
+        let frame = |c: char| AsciiSprite::new(2, 1, vec![TerminalChar::from(c); 2]);
+        let path = "test_write_frames_from_iter.bin";
+        let empty = AsciiVideo::write_frames_from_iter(path, 0, 1, 0, []);
+        assert_eq!(empty.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(!Path::new(path).exists());
+        let written = AsciiVideo::write_frames_from_iter(path, 2, 1, 3, "abc".chars().map(frame));
+        let read = AsciiVideo::read_from_file(path);
+        let short = AsciiVideo::write_frames_from_iter(path, 2, 1, 3, "ab".chars().map(frame));
+        let long = AsciiVideo::write_frames_from_iter(path, 2, 1, 1, "ab".chars().map(frame));
+        let wrong_size = AsciiVideo::write_frames_from_iter(path, 3, 1, 1, "a".chars().map(frame));
+        let failing = AsciiVideo::write_frames_from_iter(
+            path,
+            2,
+            1,
+            2,
+            [frame('a'), Err(io::ErrorKind::Other.into())],
+        );
+        std::fs::remove_file(path).unwrap();
+
+        written.unwrap();
+        let read = read.unwrap();
+        assert_eq!(read.frames.len(), 3);
+        assert_eq!(read.frames[2].to_plain_string(), "cc");
+        for err in [short, long, wrong_size] {
+            assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(failing.unwrap_err().kind(), io::ErrorKind::Other);
+    }
+}